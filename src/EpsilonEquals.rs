@@ -0,0 +1,14 @@
+use crate::Tolerance::Tolerance;
+
+/// Compares values that have been through floating point arithmetic, where exact equality is meaningless
+pub trait EpsilonEquals
+{
+    /// Returns true if the two values are equal within the given tolerance
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool;
+}
+
+impl EpsilonEquals for f64 {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(*self, *other)
+    }
+}
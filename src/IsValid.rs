@@ -1,4 +1,12 @@
+use crate::Tolerance::Tolerance;
+
 pub trait IsValid
 {
     fn is_valid(&self) -> bool;
-}
\ No newline at end of file
+
+    /// Checks validity against a model tolerance, so geometry smaller than the tolerance is rejected.
+    /// Defaults to `is_valid` for types that have no size to speak of
+    fn is_valid_within(&self, _tolerance:&Tolerance) -> bool {
+        self.is_valid()
+    }
+}
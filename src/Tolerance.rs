@@ -0,0 +1,85 @@
+use std::f64::consts::PI;
+
+/// The tolerances a model is built to.
+/// Geometry that has been through any arithmetic is compared using these rather than exact equality
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tolerance
+{
+    /// The largest distance between two values that are still considered equal
+    pub absolute: f64,
+    /// The largest angle, in radians, between two directions that are still considered equal
+    pub angle: f64,
+    /// The largest difference as a fraction of the compared values' magnitude.
+    /// This keeps comparisons meaningful far from the origin, where absolute tolerance drops below float precision
+    pub relative: f64,
+}
+
+impl Tolerance {
+
+    // Constants
+
+    /// A typical model tolerance, 0.001 units and 1 degree
+    pub const DEFAULT:Tolerance = Tolerance { absolute:0.001f64, angle:PI / 180f64, relative:1e-10f64 };
+
+    /// Exact comparison, nothing is forgiven
+    pub const EXACT:Tolerance = Tolerance { absolute:0f64, angle:0f64, relative:0f64 };
+
+    // Constructors
+
+    /// Constructs a new Tolerance
+    pub fn new(absolute:f64, angle:f64, relative:f64) -> Tolerance {
+        Tolerance { absolute, angle, relative }
+    }
+
+    // Methods
+
+    /// The allowed difference between two values of the given magnitude
+    pub fn allowance(&self, magnitude:f64) -> f64 {
+        self.absolute.max(self.relative * magnitude.abs())
+    }
+
+    /// Returns true if the two values are equal within tolerance
+    pub fn equals(&self, a:f64, b:f64) -> bool {
+        (a - b).abs() <= self.allowance(a.abs().max(b.abs()))
+    }
+
+    /// Returns true if the value is zero within the absolute tolerance
+    pub fn is_zero(&self, value:f64) -> bool {
+        value.abs() <= self.absolute
+    }
+
+    /// Returns true if the two angles, in radians, are equal within the angle tolerance
+    pub fn angle_equals(&self, a:f64, b:f64) -> bool {
+        (a - b).abs() <= self.angle
+    }
+
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn equals() {
+        let tolerance = Tolerance::DEFAULT;
+        assert!(tolerance.equals(1f64, 1.0005f64));
+        assert!(!tolerance.equals(1f64, 1.002f64));
+        assert!(!Tolerance::EXACT.equals(0.1f64 + 0.2f64, 0.3f64));
+        assert!(tolerance.equals(0.1f64 + 0.2f64, 0.3f64));
+    }
+
+    #[test]
+    pub fn relative_far_from_origin() {
+        let tolerance = Tolerance::new(1e-9f64, 0f64, 1e-12f64);
+        let far = 1e8f64;
+        assert!(tolerance.equals(far, far + 1e-5f64));
+        assert!(!tolerance.equals(far, far + 1f64));
+    }
+
+}
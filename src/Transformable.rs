@@ -0,0 +1,32 @@
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::geometry::Transform::{Transform, TransformError};
+
+/// Geometry that can be moved, rotated, scaled and mirrored by a Transform
+pub trait Transformable
+{
+    /// Transforms the geometry in place.
+    /// If the result cannot be represented by this type the geometry is left untouched and an error is returned
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError>;
+
+    /// Moves the geometry along the motion vector
+    fn translate(&mut self, motion:&Vector3d) -> Result<(), TransformError> {
+        self.transform(&Transform::translation(motion))
+    }
+
+    /// Rotates the geometry by `angle` radians about `axis`, passing through `center`
+    fn rotate(&mut self, angle:f64, axis:&Vector3d, center:&Point3d) -> Result<(), TransformError> {
+        self.transform(&Transform::rotation(angle, axis, center))
+    }
+
+    /// Uniformly scales the geometry about `center`
+    fn scale(&mut self, center:&Point3d, factor:f64) -> Result<(), TransformError> {
+        self.transform(&Transform::scale(center, factor))
+    }
+
+    /// Reflects the geometry through `plane`
+    fn mirror(&mut self, plane:&Plane) -> Result<(), TransformError> {
+        self.transform(&Transform::mirror(plane))
+    }
+}
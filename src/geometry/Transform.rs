@@ -1,3 +1,545 @@
+use std::fmt;
+use std::ops;
+
+use crate::IsValid::IsValid;
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+
+/// A 4x4 matrix describing an affine or projective transformation.
+///
+/// The matrix is stored row-major, `m[row][column]`, and is applied to column vectors,
+/// so the translation lives in the last column and the projective terms in the last row.
+#[derive(Copy, Clone, Debug)]
 pub struct Transform {
-    
-}
\ No newline at end of file
+    /// The matrix values, indexed as `m[row][column]`
+    pub m: [[f64; 4]; 4],
+}
+
+impl Transform {
+
+    // Constants
+
+    /// The Identity Transform, which leaves geometry untouched
+    pub const IDENTITY:Transform = Transform { m:[[1f64, 0f64, 0f64, 0f64],
+                                                 [0f64, 1f64, 0f64, 0f64],
+                                                 [0f64, 0f64, 1f64, 0f64],
+                                                 [0f64, 0f64, 0f64, 1f64]] };
+
+    /// A Transform full of zeros
+    pub const ZERO:Transform = Transform { m:[[0f64; 4]; 4] };
+
+    /// An Unset Transform. A completely invalid transform
+    pub const UNSET:Transform = Transform { m:[[f64::NAN; 4]; 4] };
+
+    // Constructors
+
+    /// Constructs a new Transform from a row-major matrix
+    pub fn new(m:[[f64; 4]; 4]) -> Transform {
+        Transform { m }
+    }
+
+    /// Constructs a Transform that moves geometry along the motion vector
+    pub fn translation(motion:&Vector3d) -> Transform {
+        let mut xform = Transform::IDENTITY;
+        xform.m[0][3] = motion.x;
+        xform.m[1][3] = motion.y;
+        xform.m[2][3] = motion.z;
+        xform
+    }
+
+    /// Constructs a rotation of `angle` radians about `axis`, passing through `center`.
+    /// Returns an Unset Transform if the axis has no length
+    pub fn rotation(angle:f64, axis:&Vector3d, center:&Point3d) -> Transform {
+        let length = axis.length();
+        if length == 0f64 || !length.is_finite() {
+            return Transform::UNSET;
+        }
+
+        let (x, y, z) = (axis.x / length, axis.y / length, axis.z / length);
+        let (sin, cos) = angle.sin_cos();
+        let t = 1f64 - cos;
+
+        let linear = [[t * x * x + cos,     t * x * y - sin * z, t * x * z + sin * y],
+                      [t * x * y + sin * z, t * y * y + cos,     t * y * z - sin * x],
+                      [t * x * z - sin * y, t * y * z + sin * x, t * z * z + cos]];
+
+        Transform::about_center(&linear, center)
+    }
+
+    /// Constructs a rotation about the world origin from the quaternion `w + xi + yj + zk`.
+    /// The quaternion does not need to be unitized. Returns an Unset Transform for a zero quaternion
+    pub fn rotation_from_quaternion(w:f64, x:f64, y:f64, z:f64) -> Transform {
+        let length = (w * w + x * x + y * y + z * z).sqrt();
+        if length == 0f64 || !length.is_finite() {
+            return Transform::UNSET;
+        }
+
+        let (w, x, y, z) = (w / length, x / length, y / length, z / length);
+
+        let linear = [[1f64 - 2f64 * (y * y + z * z), 2f64 * (x * y - z * w),         2f64 * (x * z + y * w)],
+                      [2f64 * (x * y + z * w),         1f64 - 2f64 * (x * x + z * z), 2f64 * (y * z - x * w)],
+                      [2f64 * (x * z - y * w),         2f64 * (y * z + x * w),         1f64 - 2f64 * (x * x + y * y)]];
+
+        Transform::about_center(&linear, &Point3d::ORIGIN)
+    }
+
+    /// Constructs a rotation about the world origin from Euler angles in radians.
+    /// The roll is applied about the X axis first, then pitch about Y, then yaw about Z
+    pub fn rotation_zyx(yaw:f64, pitch:f64, roll:f64) -> Transform {
+        let rz = Transform::rotation(yaw, &Vector3d::ZAXIS, &Point3d::ORIGIN);
+        let ry = Transform::rotation(pitch, &Vector3d::YAXIS, &Point3d::ORIGIN);
+        let rx = Transform::rotation(roll, &Vector3d::XAXIS, &Point3d::ORIGIN);
+
+        rz * ry * rx
+    }
+
+    /// Constructs a uniform scale about `center`
+    pub fn scale(center:&Point3d, factor:f64) -> Transform {
+        let linear = [[factor, 0f64, 0f64],
+                      [0f64, factor, 0f64],
+                      [0f64, 0f64, factor]];
+
+        Transform::about_center(&linear, center)
+    }
+
+    /// Constructs a non-uniform scale along the axes of `plane`, about the plane origin
+    pub fn scale_non_uniform(plane:&Plane, x:f64, y:f64, z:f64) -> Transform {
+        let mut scale = Transform::IDENTITY;
+        scale.m[0][0] = x;
+        scale.m[1][1] = y;
+        scale.m[2][2] = z;
+
+        Transform::to_world(plane) * scale * Transform::from_world(plane)
+    }
+
+    /// Constructs a reflection through `plane`
+    pub fn mirror(plane:&Plane) -> Transform {
+        let normal = Vector3d::unitize(&plane.z);
+        let (x, y, z) = (normal.x, normal.y, normal.z);
+
+        let linear = [[1f64 - 2f64 * x * x, -2f64 * x * y,       -2f64 * x * z],
+                      [-2f64 * x * y,       1f64 - 2f64 * y * y, -2f64 * y * z],
+                      [-2f64 * x * z,       -2f64 * y * z,       1f64 - 2f64 * z * z]];
+
+        Transform::about_center(&linear, &plane.origin)
+    }
+
+    /// Constructs a shear which maps the X, Y and Z axes of `plane` onto `x`, `y` and `z`,
+    /// keeping the plane origin fixed
+    pub fn shear(plane:&Plane, x:&Vector3d, y:&Vector3d, z:&Vector3d) -> Transform {
+        let mut target = Transform::IDENTITY;
+        for (column, axis) in [x, y, z].iter().enumerate() {
+            target.m[0][column] = axis.x;
+            target.m[1][column] = axis.y;
+            target.m[2][column] = axis.z;
+        }
+        target.m[0][3] = plane.origin.x;
+        target.m[1][3] = plane.origin.y;
+        target.m[2][3] = plane.origin.z;
+
+        target * Transform::from_world(plane)
+    }
+
+    /// Constructs the change of basis that maps geometry described relative to `from`
+    /// onto the same relative position on `to`
+    pub fn plane_to_plane(from:&Plane, to:&Plane) -> Transform {
+        Transform::to_world(to) * Transform::from_world(from)
+    }
+
+    /// Constructs a perspective projection, looking down the negative Z axis.
+    /// `fov_y` is the vertical field of view in radians, and `near`/`far` are positive clipping distances.
+    /// Points inside the view frustum are mapped into the cube from -1 to 1
+    pub fn perspective(fov_y:f64, aspect:f64, near:f64, far:f64) -> Transform {
+        let f = 1f64 / (fov_y / 2f64).tan();
+
+        Transform::new([[f / aspect, 0f64, 0f64, 0f64],
+                        [0f64, f, 0f64, 0f64],
+                        [0f64, 0f64, (far + near) / (near - far), (2f64 * far * near) / (near - far)],
+                        [0f64, 0f64, -1f64, 0f64]])
+    }
+
+    // Methods
+
+    /// Returns the transposed matrix
+    pub fn transpose(&self) -> Transform {
+        let mut result = Transform::ZERO;
+        for row in 0..4 {
+            for column in 0..4 {
+                result.m[row][column] = self.m[column][row];
+            }
+        }
+        result
+    }
+
+    /// Returns the determinant of the full 4x4 matrix
+    pub fn determinant(&self) -> f64 {
+        let m = &self.m;
+        let mut det = 0f64;
+        for column in 0..4 {
+            let sign = if column % 2 == 0 { 1f64 } else { -1f64 };
+            det += sign * m[0][column] * Transform::minor(m, 0, column);
+        }
+        det
+    }
+
+    /// Returns the determinant of the upper-left 3x3 matrix, which is the
+    /// volume scale of the linear part of an affine transform
+    pub fn linear_determinant(&self) -> f64 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) -
+        m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0]) +
+        m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Returns the inverse transform, or None if the matrix is singular
+    pub fn inverse(&self) -> Option<Transform> {
+        let mut a = self.m;
+        let mut inv = Transform::IDENTITY.m;
+
+        for column in 0..4 {
+            let mut pivot = column;
+            for row in column + 1..4 {
+                if a[row][column].abs() > a[pivot][column].abs() {
+                    pivot = row;
+                }
+            }
+
+            if a[pivot][column] == 0f64 || !a[pivot][column].is_finite() {
+                return None;
+            }
+
+            a.swap(column, pivot);
+            inv.swap(column, pivot);
+
+            let factor = a[column][column];
+            for k in 0..4 {
+                a[column][k] /= factor;
+                inv[column][k] /= factor;
+            }
+
+            for row in 0..4 {
+                if row == column {
+                    continue;
+                }
+                let factor = a[row][column];
+                for k in 0..4 {
+                    a[row][k] -= factor * a[column][k];
+                    inv[row][k] -= factor * inv[column][k];
+                }
+            }
+        }
+
+        Some(Transform::new(inv))
+    }
+
+    /// Returns true if the bottom row is (0, 0, 0, 1), so no perspective divide is needed
+    pub fn is_affine(&self) -> bool {
+        self.m[3][0] == 0f64 &&
+        self.m[3][1] == 0f64 &&
+        self.m[3][2] == 0f64 &&
+        self.m[3][3] == 1f64
+    }
+
+    /// Returns true if the transform is the identity
+    pub fn is_identity(&self) -> bool {
+        self.m == Transform::IDENTITY.m
+    }
+
+    /// Returns true if the transform preserves angles and scales all lengths equally.
+    /// Rotations, translations, mirrors and uniform scales are all similarities
+    pub fn is_similarity(&self) -> bool {
+        self.similarity_scale().is_some()
+    }
+
+    /// Returns true if the transform preserves all lengths, i.e. a combination of
+    /// rotations, translations and mirrors
+    pub fn is_rigid(&self) -> bool {
+        match self.similarity_scale() {
+            Some(scale) => (scale - 1f64).abs() <= Transform::TOLERANCE,
+            None => false,
+        }
+    }
+
+    /// Returns the uniform scale factor applied to lengths if the transform is a similarity
+    pub fn similarity_scale(&self) -> Option<f64> {
+        if !self.is_valid() || !self.is_affine() {
+            return None;
+        }
+
+        let columns = self.linear_columns();
+        let scale_squared = Transform::dot(&columns[0], &columns[0]);
+        if scale_squared == 0f64 {
+            return None;
+        }
+
+        for i in 0..3 {
+            for j in i..3 {
+                let expected = if i == j { scale_squared } else { 0f64 };
+                let actual = Transform::dot(&columns[i], &columns[j]);
+                if (actual - expected).abs() > Transform::TOLERANCE * scale_squared {
+                    return None;
+                }
+            }
+        }
+
+        Some(scale_squared.sqrt())
+    }
+
+    // Helpers
+
+    /// The relative tolerance used when classifying transforms
+    pub(crate) const TOLERANCE:f64 = 1e-10;
+
+    /// Builds an affine transform from a 3x3 linear part that keeps `center` fixed
+    fn about_center(linear:&[[f64; 3]; 3], center:&Point3d) -> Transform {
+        let mut xform = Transform::IDENTITY;
+        let c = [center.x, center.y, center.z];
+        for row in 0..3 {
+            let mut moved = 0f64;
+            for column in 0..3 {
+                xform.m[row][column] = linear[row][column];
+                moved += linear[row][column] * c[column];
+            }
+            xform.m[row][3] = c[row] - moved;
+        }
+        xform
+    }
+
+    /// Maps world coordinates into the coordinates of `plane`
+    fn from_world(plane:&Plane) -> Transform {
+        let o = &plane.origin;
+        let mut xform = Transform::IDENTITY;
+        for (row, axis) in [&plane.x, &plane.y, &plane.z].iter().enumerate() {
+            xform.m[row][0] = axis.x;
+            xform.m[row][1] = axis.y;
+            xform.m[row][2] = axis.z;
+            xform.m[row][3] = -(axis.x * o.x + axis.y * o.y + axis.z * o.z);
+        }
+        xform
+    }
+
+    /// Maps coordinates relative to `plane` back into world coordinates
+    fn to_world(plane:&Plane) -> Transform {
+        let mut xform = Transform::IDENTITY;
+        for (column, axis) in [&plane.x, &plane.y, &plane.z].iter().enumerate() {
+            xform.m[0][column] = axis.x;
+            xform.m[1][column] = axis.y;
+            xform.m[2][column] = axis.z;
+        }
+        xform.m[0][3] = plane.origin.x;
+        xform.m[1][3] = plane.origin.y;
+        xform.m[2][3] = plane.origin.z;
+        xform
+    }
+
+    /// The three columns of the linear part
+    fn linear_columns(&self) -> [[f64; 3]; 3] {
+        let m = &self.m;
+        [[m[0][0], m[1][0], m[2][0]],
+         [m[0][1], m[1][1], m[2][1]],
+         [m[0][2], m[1][2], m[2][2]]]
+    }
+
+    fn dot(a:&[f64; 3], b:&[f64; 3]) -> f64 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+
+    /// The determinant of the 3x3 matrix left after removing `row` and `column`
+    fn minor(m:&[[f64; 4]; 4], row:usize, column:usize) -> f64 {
+        let mut sub = [[0f64; 3]; 3];
+        for (r, i) in (0..4).filter(|i| *i != row).enumerate() {
+            for (c, j) in (0..4).filter(|j| *j != column).enumerate() {
+                sub[r][c] = m[i][j];
+            }
+        }
+
+        sub[0][0] * (sub[1][1] * sub[2][2] - sub[1][2] * sub[2][1]) -
+        sub[0][1] * (sub[1][0] * sub[2][2] - sub[1][2] * sub[2][0]) +
+        sub[0][2] * (sub[1][0] * sub[2][1] - sub[1][1] * sub[2][0])
+    }
+
+}
+
+impl ops::Mul<Transform> for Transform {
+    type Output = Transform;
+    /// Composes two transforms. `a * b` applies `b` first, then `a`
+    fn mul(self, rhs: Transform) -> Self::Output {
+        let mut result = Transform::ZERO;
+        for row in 0..4 {
+            for column in 0..4 {
+                for k in 0..4 {
+                    result.m[row][column] += self.m[row][k] * rhs.m[k][column];
+                }
+            }
+        }
+        result
+    }
+}
+
+/// The reasons a piece of geometry can refuse a Transform
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TransformError {
+    /// The transform contains NaN or infinite values
+    Invalid,
+    /// The result would be a different kind of shape, e.g. a circle stretched into an ellipse
+    ShapeNotPreserved,
+    /// The result collapses, e.g. a point sent to infinity or an axis scaled to nothing
+    Degenerate,
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::Invalid => write!(f, "the transform is not valid"),
+            TransformError::ShapeNotPreserved => write!(f, "the transform does not preserve the shape of the geometry"),
+            TransformError::Degenerate => write!(f, "the transform collapses the geometry"),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+impl IsValid for Transform {
+    fn is_valid(&self) -> bool {
+        self.m.iter().flatten().all(|v| v.is_finite())
+    }
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.m == other.m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Transformable::Transformable;
+    use std::f64::consts::PI;
+
+    fn assert_close(expected:f64, actual:f64) {
+        assert!((expected - actual).abs() < 1e-9, "expected {} but got {}", expected, actual);
+    }
+
+    #[test]
+    pub fn translation() {
+        let mut point = Point3d::new(1f64, 2f64, 3f64);
+        point.transform(&Transform::translation(&Vector3d::new(10f64, 20f64, 30f64))).unwrap();
+
+        assert_close(11f64, point.x);
+        assert_close(22f64, point.y);
+        assert_close(33f64, point.z);
+    }
+
+    #[test]
+    pub fn rotation_about_center() {
+        let center = Point3d::new(1f64, 0f64, 0f64);
+        let rotation = Transform::rotation(PI / 2f64, &Vector3d::ZAXIS, &center);
+
+        let mut point = Point3d::new(2f64, 0f64, 0f64);
+        point.transform(&rotation).unwrap();
+
+        assert_close(1f64, point.x);
+        assert_close(1f64, point.y);
+        assert_close(0f64, point.z);
+        assert!(rotation.is_rigid());
+    }
+
+    #[test]
+    pub fn quaternion_matches_axis_angle() {
+        let half = PI / 8f64;
+        let quaternion = Transform::rotation_from_quaternion(half.cos(), 0f64, 0f64, half.sin());
+        let axis_angle = Transform::rotation(PI / 4f64, &Vector3d::ZAXIS, &Point3d::ORIGIN);
+
+        for row in 0..4 {
+            for column in 0..4 {
+                assert_close(axis_angle.m[row][column], quaternion.m[row][column]);
+            }
+        }
+    }
+
+    #[test]
+    pub fn euler_rotation() {
+        let mut vector = Vector3d::XAXIS;
+        vector.transform(&Transform::rotation_zyx(PI / 2f64, 0f64, 0f64)).unwrap();
+
+        assert_close(0f64, vector.x);
+        assert_close(1f64, vector.y);
+        assert_close(0f64, vector.z);
+    }
+
+    #[test]
+    pub fn inverse() {
+        let xform = Transform::translation(&Vector3d::new(5f64, 0f64, 0f64)) *
+                    Transform::rotation(1f64, &Vector3d::new(1f64, 1f64, 0f64), &Point3d::ORIGIN) *
+                    Transform::scale(&Point3d::ORIGIN, 3f64);
+
+        let identity = xform * xform.inverse().unwrap();
+        for row in 0..4 {
+            for column in 0..4 {
+                assert_close(Transform::IDENTITY.m[row][column], identity.m[row][column]);
+            }
+        }
+
+        assert!(Transform::ZERO.inverse().is_none());
+    }
+
+    #[test]
+    pub fn determinant() {
+        assert_close(1f64, Transform::IDENTITY.determinant());
+        assert_close(8f64, Transform::scale(&Point3d::ORIGIN, 2f64).determinant());
+        assert_close(-1f64, Transform::mirror(&Plane::WORLDXY).determinant());
+    }
+
+    #[test]
+    pub fn mirror() {
+        let mut point = Point3d::new(1f64, 2f64, 3f64);
+        point.transform(&Transform::mirror(&Plane::WORLDXY)).unwrap();
+
+        assert_close(1f64, point.x);
+        assert_close(2f64, point.y);
+        assert_close(-3f64, point.z);
+    }
+
+    #[test]
+    pub fn classification() {
+        let scale = Transform::scale(&Point3d::ORIGIN, 2f64);
+        assert!(scale.is_similarity());
+        assert!(!scale.is_rigid());
+
+        let stretch = Transform::scale_non_uniform(&Plane::WORLDXY, 1f64, 2f64, 1f64);
+        assert!(!stretch.is_similarity());
+
+        let perspective = Transform::perspective(PI / 2f64, 1f64, 1f64, 100f64);
+        assert!(!perspective.is_affine());
+        assert!(!perspective.is_similarity());
+    }
+
+    #[test]
+    pub fn plane_to_plane() {
+        let to = Plane::new(Point3d::new(0f64, 0f64, 5f64), Vector3d::YAXIS, Vector3d::new(-1f64, 0f64, 0f64)).unwrap();
+        let mut point = Point3d::new(1f64, 0f64, 0f64);
+        point.transform(&Transform::plane_to_plane(&Plane::WORLDXY, &to)).unwrap();
+
+        assert_close(0f64, point.x);
+        assert_close(1f64, point.y);
+        assert_close(5f64, point.z);
+    }
+
+    #[test]
+    pub fn perspective_divide() {
+        let perspective = Transform::perspective(PI / 2f64, 1f64, 1f64, 100f64);
+
+        let mut near = Point3d::new(1f64, 1f64, -1f64);
+        near.transform(&perspective).unwrap();
+        assert_close(1f64, near.x);
+        assert_close(1f64, near.y);
+        assert_close(-1f64, near.z);
+
+        let mut far = Point3d::new(0f64, 0f64, -100f64);
+        far.transform(&perspective).unwrap();
+        assert_close(1f64, far.z);
+    }
+
+}
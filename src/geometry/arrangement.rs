@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+
+use crate::geometry::basics::Line2d::Line2d;
+use crate::geometry::basics::Point2d::Point2d;
+use crate::geometry::basics::Vector2d::Vector2d;
+
+// Planar algorithms such as offsetting and booleans start from a loose collection of directed edges.
+// The edges are cut wherever they cross or touch, so that afterwards two edges either share exactly the same ends or
+// meet only at their ends. Each piece can then be kept or thrown away on its own, and the kept ones stitched into polylines.
+
+/// A directed straight edge, with a tag recording where it came from
+#[derive(Copy, Clone)]
+pub(crate) struct Edge<T> {
+    pub start:Point2d,
+    pub end:Point2d,
+    pub tag:T,
+}
+
+/// A piece of an edge running between two vertices of the arrangement
+#[derive(Copy, Clone)]
+pub(crate) struct Piece<T> {
+    pub from:usize,
+    pub to:usize,
+    pub tag:T,
+}
+
+/// Edges cut against each other, with ends closer than the tolerance merged into shared vertices
+pub(crate) struct Arrangement<T> {
+    pub vertices:Vec<Point2d>,
+    pub pieces:Vec<Piece<T>>,
+    tolerance:f64,
+}
+
+impl<T:Copy> Arrangement<T> {
+
+    /// Cuts every edge where it crosses another, and where an end of another lies within `tolerance` of it
+    pub fn new(edges:&[Edge<T>], tolerance:f64) -> Arrangement<T> {
+        let mut cuts:Vec<Vec<f64>> = vec![vec![0f64, 1f64]; edges.len()];
+        for first in 0..edges.len() {
+            for second in first + 1..edges.len() {
+                let (a, b) = (&edges[first], &edges[second]);
+                if !boxes_touch(a, b, tolerance) {
+                    continue;
+                }
+
+                if let Some((s, t)) = crossing(a, b) {
+                    cuts[first].push(s);
+                    cuts[second].push(t);
+                }
+                for (edge, other, index) in [(a, b, first), (b, a, second)] {
+                    let line = Line2d::new(edge.start, edge.end);
+                    for point in [other.start, other.end] {
+                        let t = line.closest_parameter(&point);
+                        if t > 0f64 && t < 1f64 && line.point_at(t).distance_to(&point) <= tolerance {
+                            cuts[index].push(t);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut arrangement = Arrangement { vertices:Vec::new(), pieces:Vec::new(), tolerance };
+        let mut grid:HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (edge, mut edge_cuts) in edges.iter().zip(cuts) {
+            edge_cuts.sort_by(|a, b| a.total_cmp(b));
+            let line = Line2d::new(edge.start, edge.end);
+            let ids:Vec<usize> = edge_cuts.iter()
+                .map(|t| arrangement.vertex(line.point_at(*t), tolerance, &mut grid))
+                .collect();
+
+            for pair in ids.windows(2) {
+                if pair[0] != pair[1] {
+                    arrangement.pieces.push(Piece { from:pair[0], to:pair[1], tag:edge.tag });
+                }
+            }
+        }
+
+        arrangement
+    }
+
+    /// Returns how many times the pieces accepted by `filter` wind anticlockwise around the point
+    pub fn winding(&self, point:Point2d, filter:impl Fn(&T) -> bool) -> i64 {
+        self.pieces.iter()
+            .filter(|piece| filter(&piece.tag))
+            .map(|piece| crossing_sign(self.vertices[piece.from], self.vertices[piece.to], point))
+            .sum()
+    }
+
+    /// Returns how many of the pieces accepted by `filter` a ray from the point crosses, whichever way they go
+    pub fn crossings(&self, point:Point2d, filter:impl Fn(&T) -> bool) -> i64 {
+        self.pieces.iter()
+            .filter(|piece| filter(&piece.tag))
+            .map(|piece| crossing_sign(self.vertices[piece.from], self.vertices[piece.to], point).abs())
+            .sum()
+    }
+
+    /// Returns which pieces bound a region, having `inside` true just to their left and false just to their right.
+    /// Of pieces lying exactly on top of each other in the same direction only the first can be kept, and pieces lying on top of
+    /// each other in opposite directions see the same thing on both sides, so coincident edges resolve without special cases
+    pub fn boundary(&self, inside:impl Fn(Point2d) -> bool) -> Vec<bool> {
+        let mut seen = HashMap::new();
+        self.pieces.iter().enumerate()
+            .map(|(index, piece)| {
+                if *seen.entry((piece.from, piece.to)).or_insert(index) != index {
+                    return false;
+                }
+
+                let (from, to) = (self.vertices[piece.from], self.vertices[piece.to]);
+                let middle = from.interpolate(&to, 0.5f64);
+                let step = Vector2d::unitize(&(to - from)).perpendicular() * (from.distance_to(&to) * 1e-3f64).min(self.tolerance);
+                inside(middle + step) && !inside(middle - step)
+            })
+            .collect()
+    }
+
+    /// Joins the kept pieces end to start into polylines. Chains are followed from wherever more pieces leave than arrive,
+    /// and the rest must form loops, which end on their first point. Where there is a choice the sharpest left turn is taken,
+    /// so loops that only touch at a corner come out separately
+    pub fn stitch(&self, keep:&[bool]) -> Vec<Vec<Point2d>> {
+        let mut leaving:Vec<Vec<usize>> = vec![Vec::new(); self.vertices.len()];
+        let mut balance = vec![0i64; self.vertices.len()];
+        for (index, piece) in self.pieces.iter().enumerate() {
+            if keep[index] {
+                leaving[piece.from].push(index);
+                balance[piece.from] += 1;
+                balance[piece.to] -= 1;
+            }
+        }
+
+        let mut used = vec![false; self.pieces.len()];
+        let mut results = Vec::new();
+        for (vertex, surplus) in balance.iter().enumerate() {
+            for _ in 0..*surplus {
+                if let Some(&first) = leaving[vertex].iter().find(|index| !used[**index]) {
+                    results.push(self.follow(first, true, &leaving, &mut used));
+                }
+            }
+        }
+        for vertex in 0..self.vertices.len() {
+            while let Some(&first) = leaving[vertex].iter().find(|index| !used[**index]) {
+                results.push(self.follow(first, false, &leaving, &mut used));
+            }
+        }
+
+        results
+    }
+
+    /// Follows unused kept pieces on from `first` until there are none, or for a loop until it is back where it started
+    fn follow(&self, first:usize, is_chain:bool, leaving:&[Vec<usize>], used:&mut [bool]) -> Vec<Point2d> {
+        let start = self.pieces[first].from;
+        used[first] = true;
+        let mut points = vec![self.vertices[start], self.vertices[self.pieces[first].to]];
+        let mut current = first;
+        while is_chain || self.pieces[current].to != start {
+            let Some(next) = self.next_piece(current, leaving, used) else {
+                break;
+            };
+
+            used[next] = true;
+            points.push(self.vertices[self.pieces[next].to]);
+            current = next;
+        }
+
+        points
+    }
+
+    /// Returns the unused kept piece leaving the end of `current` that turns furthest to the left
+    fn next_piece(&self, current:usize, leaving:&[Vec<usize>], used:&[bool]) -> Option<usize> {
+        let piece = &self.pieces[current];
+        let incoming = self.vertices[piece.to] - self.vertices[piece.from];
+        leaving[piece.to].iter()
+            .copied()
+            .filter(|index| !used[*index])
+            .max_by(|a, b| {
+                let turn = |index:usize| {
+                    let outgoing = self.vertices[self.pieces[index].to] - self.vertices[piece.to];
+                    incoming.signed_angle_to(&outgoing)
+                };
+                turn(*a).total_cmp(&turn(*b))
+            })
+    }
+
+    /// Returns the vertex within tolerance of the point, adding a new one if there is none
+    fn vertex(&mut self, point:Point2d, tolerance:f64, grid:&mut HashMap<(i64, i64), Vec<usize>>) -> usize {
+        let size = tolerance.max(f64::MIN_POSITIVE);
+        let cell = ((point.x / size).floor() as i64, (point.y / size).floor() as i64);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let mut nearby = grid.get(&(cell.0 + dx, cell.1 + dy)).into_iter().flatten();
+                if let Some(&found) = nearby.find(|id| self.vertices[**id].distance_to(&point) <= tolerance) {
+                    return found;
+                }
+            }
+        }
+
+        self.vertices.push(point);
+        grid.entry(cell).or_default().push(self.vertices.len() - 1);
+        self.vertices.len() - 1
+    }
+
+}
+
+/// Returns 1 if the edge from `from` to `to` crosses the ray from the point along +X going up, -1 going down and 0 if it misses.
+/// Each edge counts its lower end but not its upper one, so a ray through a vertex is counted once
+pub(crate) fn crossing_sign(from:Point2d, to:Point2d, point:Point2d) -> i64 {
+    let left = (to - from).cross(&(point - from)) > 0f64;
+    if from.y <= point.y && to.y > point.y && left {
+        1
+    } else if to.y <= point.y && from.y > point.y && !left {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Returns the area enclosed by a loop of points, positive if it winds anticlockwise.
+/// The loop closes itself, so the last point may or may not repeat the first
+pub(crate) fn signed_area(points:impl IntoIterator<Item = Point2d>) -> f64 {
+    let mut points = points.into_iter();
+    let Some(origin) = points.next() else {
+        return 0f64;
+    };
+
+    let mut area = 0f64;
+    let mut previous = origin;
+    for point in points {
+        area += (previous - origin).cross(&(point - origin));
+        previous = point;
+    }
+
+    area * 0.5f64
+}
+
+fn boxes_touch<T>(a:&Edge<T>, b:&Edge<T>, tolerance:f64) -> bool {
+    a.start.x.max(a.end.x) + tolerance >= b.start.x.min(b.end.x) && b.start.x.max(b.end.x) + tolerance >= a.start.x.min(a.end.x) &&
+    a.start.y.max(a.end.y) + tolerance >= b.start.y.min(b.end.y) && b.start.y.max(b.end.y) + tolerance >= a.start.y.min(a.end.y)
+}
+
+/// Returns the parameters on each edge where they cross, if each has its ends strictly on either side of the other.
+/// Ends lying on the other edge are found as touches instead
+fn crossing<T>(a:&Edge<T>, b:&Edge<T>) -> Option<(f64, f64)> {
+    let orient = |start:Point2d, end:Point2d, point:Point2d| (end - start).cross(&(point - start));
+    let (a0, a1) = (orient(b.start, b.end, a.start), orient(b.start, b.end, a.end));
+    let (b0, b1) = (orient(a.start, a.end, b.start), orient(a.start, a.end, b.end));
+    if a0 * a1 >= 0f64 || b0 * b1 >= 0f64 {
+        return None;
+    }
+
+    Some((a0 / (a0 - a1), b0 / (b0 - b1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(start:(f64, f64), end:(f64, f64)) -> Edge<()> {
+        Edge { start:Point2d::new(start.0, start.1), end:Point2d::new(end.0, end.1), tag:() }
+    }
+
+    #[test]
+    pub fn cuts_crossings_touches_and_overlaps() {
+        let arrangement = Arrangement::new(&[edge((0f64, 0f64), (4f64, 0f64)),
+                                             edge((2f64, -1f64), (2f64, 1f64)),
+                                             edge((3f64, 0f64), (3f64, 2f64)),
+                                             edge((1f64, 0f64), (5f64, 0f64))], 1e-9f64);
+
+        // The first edge is cut at 1, 2 and 3, the overlapping last edge at 2, 3 and 4
+        assert_eq!(9, arrangement.vertices.len());
+        assert_eq!(11, arrangement.pieces.len());
+        assert_eq!(5, arrangement.boundary(|point| point.y > 0f64).iter().filter(|keep| **keep).count());
+    }
+
+    #[test]
+    pub fn stitches_touching_loops_apart() {
+        let edges = [edge((0f64, 0f64), (1f64, 0f64)), edge((1f64, 0f64), (1f64, 1f64)),
+                     edge((1f64, 1f64), (0f64, 1f64)), edge((0f64, 1f64), (0f64, 0f64)),
+                     edge((1f64, 1f64), (2f64, 1f64)), edge((2f64, 1f64), (2f64, 2f64)),
+                     edge((2f64, 2f64), (1f64, 2f64)), edge((1f64, 2f64), (1f64, 1f64)),
+                     edge((3f64, 0f64), (4f64, 0f64))];
+        let arrangement = Arrangement::new(&edges, 1e-9f64);
+        let results = arrangement.stitch(&vec![true; arrangement.pieces.len()]);
+
+        assert_eq!(3, results.len());
+        assert_eq!(1, arrangement.winding(Point2d::new(0.5f64, 0.5f64), |_| true));
+        assert_eq!(0, arrangement.winding(Point2d::new(1.5f64, 0.5f64), |_| true));
+        assert_eq!(2, results[0].len());
+        assert!(results[1..].iter().all(|points| points.len() == 5 && points[0] == points[4]));
+    }
+
+}
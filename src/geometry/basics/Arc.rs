@@ -0,0 +1,386 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::fmt;
+
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Circle::{Circle, CircleError};
+use crate::geometry::basics::Interval::Interval;
+use crate::geometry::basics::NurbsCurve::NurbsCurve;
+use crate::geometry::basics::Plane::{Plane, PlaneError};
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
+
+/// A part of a Circle, running anticlockwise about the circle's normal
+/// from the start of its angle interval to the end
+#[derive(Copy, Clone)]
+pub struct Arc
+{
+    /// The circle the arc lies on
+    pub circle: Circle,
+
+    /// The angles, in radians, the arc starts and ends at
+    pub angle: Interval,
+
+}
+
+impl Arc {
+
+    pub const UNSET:Arc = Arc { circle:Circle::UNSET, angle:Interval::UNSET };
+
+    /// Constructs an arc on a circle between two angles.
+    /// The angle must increase by more than zero and no more than a full turn
+    pub fn new(circle:Circle, angle:Interval) -> Result<Arc, ArcError> {
+        if !(angle.is_valid() && angle.is_increasing() && angle.length() <= 2f64 * PI) {
+            return Err(ArcError::InvalidAngle);
+        }
+
+        Ok(Arc { circle, angle })
+    }
+
+    /// Constructs the arc starting at `start`, passing through `mid` and finishing at `end`
+    pub fn from_three_points(start:Point3d, mid:Point3d, end:Point3d) -> Result<Arc, ArcError> {
+        let circle = Circle::from_three_points(start, mid, end)?;
+        let sweep = circle.closest_parameter(&end).ok_or(ArcError::InvalidAngle)?;
+
+        Arc::new(circle, Interval::new(0f64, sweep))
+    }
+
+    /// Constructs the arc leaving `start` along `tangent` and finishing at `end`
+    pub fn from_start_tangent_end(start:Point3d, tangent:Vector3d, end:Point3d) -> Result<Arc, ArcError> {
+        let chord = end - start;
+        let normal = tangent.cross(&chord);
+        let normal_squared = normal.dot(&normal);
+        if normal_squared == 0f64 || !normal_squared.is_finite() {
+            return Err(ArcError::Circle(CircleError::Plane(PlaneError::Colinear)));
+        }
+
+        // The center lies along the in-plane perpendicular to the tangent,
+        // at the distance where it is equally far from both ends
+        let inward = Vector3d::unitize(&normal.cross(&tangent));
+        let radius = chord.dot(&chord) / (2f64 * chord.dot(&inward));
+        let center = start + inward * radius;
+
+        let circle = Circle::from_plane(Plane::new(center, start - center, tangent)?, radius)?;
+        let sweep = circle.closest_parameter(&end).ok_or(ArcError::InvalidAngle)?;
+
+        Arc::new(circle, Interval::new(0f64, sweep))
+    }
+
+    /// Constructs the shorter arc about `center`, starting at `start` and turning towards `end`.
+    /// The radius is taken from `start`, so `end` only sets the direction the arc finishes in
+    pub fn from_center_start_end(center:Point3d, start:Point3d, end:Point3d) -> Result<Arc, ArcError> {
+        let to_start = start - center;
+        let plane = Plane::new(center, to_start, end - center)?;
+        let circle = Circle::from_plane(plane, to_start.length())?;
+        let sweep = circle.closest_parameter(&end).ok_or(ArcError::InvalidAngle)?;
+
+        Arc::new(circle, Interval::new(0f64, sweep))
+    }
+
+    // Methods
+
+    /// Returns the length along the arc
+    pub fn length(&self) -> f64 {
+        self.circle.radius * self.angle.length()
+    }
+
+    /// Returns the point the arc starts at
+    pub fn start_point(&self) -> Point3d {
+        self.circle.point_at(self.angle.t0())
+    }
+
+    /// Returns the point the arc finishes at
+    pub fn end_point(&self) -> Point3d {
+        self.circle.point_at(self.angle.t1())
+    }
+
+    /// Returns the point half way along the arc
+    pub fn mid_point(&self) -> Point3d {
+        self.circle.point_at(self.angle.mid())
+    }
+
+    /// Returns the point on the arc's circle at `angle`
+    pub fn point_at(&self, angle:f64) -> Point3d {
+        self.circle.point_at(angle)
+    }
+
+    /// Returns the unit tangent at `angle`, pointing along the arc
+    pub fn tangent_at(&self, angle:f64) -> Vector3d {
+        self.circle.tangent_at(angle)
+    }
+
+    /// Returns the angle of the closest point on the arc, within the arc's angle interval.
+    /// A point on the circle's axis is equally close to every point, and gets the start
+    pub fn closest_parameter(&self, point:&Point3d) -> f64 {
+        let start = self.angle.t0();
+        let Some(on_circle) = self.circle.closest_parameter(point) else {
+            return start;
+        };
+
+        // Turn the circle angle into the full turn beginning at the start of the arc
+        let angle = start + (on_circle - start).rem_euclid(2f64 * PI);
+        if angle <= self.angle.t1() {
+            return angle;
+        }
+
+        if point.distance_to(&self.start_point()) <= point.distance_to(&self.end_point()) {
+            start
+        } else {
+            self.angle.t1()
+        }
+    }
+
+    /// Returns the closest point on the arc
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        self.point_at(self.closest_parameter(point))
+    }
+
+    /// Returns the same arc running the other way.
+    /// The circle's plane is turned over, so the angles are negated and swapped
+    pub fn reverse(&self) -> Arc {
+        let plane = Plane { y: -self.circle.plane.y, z: -self.circle.plane.z, ..self.circle.plane };
+
+        Arc {
+            circle: Circle { plane, ..self.circle },
+            angle: (self.angle * -1f64).swap(),
+        }
+    }
+
+    /// Splits the arc in two at `angle`.
+    /// Returns None unless the angle lies strictly inside the arc
+    pub fn split(&self, angle:f64) -> Option<(Arc, Arc)> {
+        let (first, second) = self.angle.split(angle)?;
+        Some((Arc { angle: first, ..*self }, Arc { angle: second, ..*self }))
+    }
+
+    /// Returns the arc as an exact rational quadratic NurbsCurve, using one span per quarter turn or part of one.
+    /// The curve's domain is the arc's angle interval, but only the knots fall on the same points as the angles.
+    /// Returns None if the arc is not valid
+    pub fn to_nurbs(&self) -> Option<NurbsCurve> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        let spans = (self.angle.length() / FRAC_PI_2).ceil().max(1f64) as usize;
+        let step = self.angle.length() / spans as f64;
+        let weight = (step / 2f64).cos();
+
+        let mut points = vec![self.start_point()];
+        let mut weights = vec![1f64];
+        let mut knots = vec![self.angle.t0(); 3];
+        for span in 0..spans {
+            let start = self.angle.t0() + step * span as f64;
+            let end = if span + 1 == spans { self.angle.t1() } else { start + step };
+
+            // The middle control point is where the tangents at either end of the span meet
+            let corner = self.circle.point_at(start + step / 2f64) - self.circle.center();
+            points.push(self.circle.center() + corner / weight);
+            points.push(self.circle.point_at(end));
+            weights.push(weight);
+            weights.push(1f64);
+            knots.push(end);
+            knots.push(end);
+        }
+        knots.push(self.angle.t1());
+
+        NurbsCurve::new(2, points, weights, knots).ok()
+    }
+
+}
+
+/// The reasons an Arc cannot be constructed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ArcError {
+    /// The angle interval is not increasing, or turns more than a full circle
+    InvalidAngle,
+    /// No circle could be made for the arc
+    Circle(CircleError),
+}
+
+impl From<CircleError> for ArcError {
+    fn from(error: CircleError) -> Self {
+        ArcError::Circle(error)
+    }
+}
+
+impl From<PlaneError> for ArcError {
+    fn from(error: PlaneError) -> Self {
+        ArcError::Circle(CircleError::Plane(error))
+    }
+}
+
+impl fmt::Display for ArcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArcError::InvalidAngle => write!(f, "the angle must increase by no more than a full turn"),
+            ArcError::Circle(error) => write!(f, "the arc has no circle, {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ArcError {}
+
+impl IsValid for Arc {
+    fn is_valid(&self) -> bool {
+        self.circle.is_valid() &&
+        self.angle.is_valid() &&
+        self.angle.length() > 0f64 &&
+        self.angle.length() <= 2f64 * PI
+    }
+
+    /// An arc is only valid if its circle is, and it is longer than the tolerance
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() &&
+        self.circle.is_valid_within(tolerance) &&
+        self.length() > tolerance.absolute
+    }
+}
+
+impl Transformable for Arc {
+    /// Transforms the circle, the angles are measured in its plane so they are unchanged
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        self.circle.transform(xform)
+    }
+}
+
+impl EpsilonEquals for Arc {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.circle.epsilon_equals(&other.circle, tolerance) &&
+        tolerance.angle_equals(self.angle.t0(), other.angle.t0()) &&
+        tolerance.angle_equals(self.angle.t1(), other.angle.t1())
+    }
+}
+
+impl PartialEq for Arc {
+    fn eq(&self, other: &Self) -> bool {
+        self.circle.eq(&other.circle) &&
+        self.angle.eq(&other.angle)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
+
+    fn quarter() -> Arc {
+        let circle = Circle::new(Point3d::ORIGIN, 2f64).unwrap();
+        Arc::new(circle, Interval::new(0f64, FRAC_PI_2)).unwrap()
+    }
+
+    #[test]
+    pub fn new_rejects_bad_angles()
+    {
+        let circle = Circle::new(Point3d::ORIGIN, 2f64).unwrap();
+
+        assert_eq!(Some(ArcError::InvalidAngle), Arc::new(circle, Interval::new(1f64, 1f64)).err());
+        assert_eq!(Some(ArcError::InvalidAngle), Arc::new(circle, Interval::new(1f64, 0f64)).err());
+        assert_eq!(Some(ArcError::InvalidAngle), Arc::new(circle, Interval::new(0f64, 7f64)).err());
+        assert!(!Arc::UNSET.is_valid());
+    }
+
+    #[test]
+    pub fn evaluation()
+    {
+        let arc = quarter();
+
+        assert!(TOLERANCE.equals(PI, arc.length()));
+        assert!(arc.start_point().epsilon_equals(&Point3d::new(2f64, 0f64, 0f64), &TOLERANCE));
+        assert!(arc.end_point().epsilon_equals(&Point3d::new(0f64, 2f64, 0f64), &TOLERANCE));
+        assert!(arc.mid_point().epsilon_equals(&Point3d::new(2f64.sqrt(), 2f64.sqrt(), 0f64), &TOLERANCE));
+    }
+
+    #[test]
+    pub fn from_three_points()
+    {
+        let arc = Arc::from_three_points(Point3d::new(1f64, 0f64, 0f64),
+                                         Point3d::new(0f64, 1f64, 0f64),
+                                         Point3d::new(-1f64, 0f64, 0f64)).unwrap();
+
+        assert!(TOLERANCE.angle_equals(PI, arc.angle.length()));
+        assert!(arc.mid_point().epsilon_equals(&Point3d::new(0f64, 1f64, 0f64), &TOLERANCE));
+    }
+
+    #[test]
+    pub fn from_start_tangent_end()
+    {
+        let arc = Arc::from_start_tangent_end(Point3d::new(1f64, 0f64, 0f64),
+                                              Vector3d::new(0f64, -3f64, 0f64),
+                                              Point3d::new(-1f64, 0f64, 0f64)).unwrap();
+
+        assert!(arc.circle.center().epsilon_equals(&Point3d::ORIGIN, &TOLERANCE));
+        assert!(TOLERANCE.equals(1f64, arc.circle.radius));
+        assert!(arc.mid_point().epsilon_equals(&Point3d::new(0f64, -1f64, 0f64), &TOLERANCE));
+        assert!(arc.tangent_at(arc.angle.min()).epsilon_equals(&-Vector3d::YAXIS, &TOLERANCE));
+
+        let straight = Arc::from_start_tangent_end(Point3d::ORIGIN, Vector3d::XAXIS, Point3d::new(2f64, 0f64, 0f64));
+        assert_eq!(Some(ArcError::Circle(CircleError::Plane(PlaneError::Colinear))), straight.err());
+    }
+
+    #[test]
+    pub fn from_center_start_end()
+    {
+        let arc = Arc::from_center_start_end(Point3d::ORIGIN,
+                                             Point3d::new(0f64, 3f64, 0f64),
+                                             Point3d::new(5f64, 0f64, 0f64)).unwrap();
+
+        assert!(TOLERANCE.angle_equals(FRAC_PI_2, arc.angle.length()));
+        assert!(arc.end_point().epsilon_equals(&Point3d::new(3f64, 0f64, 0f64), &TOLERANCE));
+    }
+
+    #[test]
+    pub fn closest_point()
+    {
+        let arc = quarter();
+
+        let inside = arc.closest_point(&Point3d::new(5f64, 5f64, 1f64));
+        assert!(inside.epsilon_equals(&arc.mid_point(), &TOLERANCE));
+
+        let near_start = arc.closest_point(&Point3d::new(3f64, -1f64, 0f64));
+        assert!(near_start.epsilon_equals(&arc.start_point(), &TOLERANCE));
+
+        let near_end = arc.closest_point(&Point3d::new(-1f64, 3f64, 0f64));
+        assert!(near_end.epsilon_equals(&arc.end_point(), &TOLERANCE));
+    }
+
+    #[test]
+    pub fn reverse_and_split()
+    {
+        let arc = quarter();
+        let reversed = arc.reverse();
+
+        assert!(reversed.is_valid());
+        assert!(reversed.start_point().epsilon_equals(&arc.end_point(), &TOLERANCE));
+        assert!(reversed.end_point().epsilon_equals(&arc.start_point(), &TOLERANCE));
+        assert!(reversed.mid_point().epsilon_equals(&arc.mid_point(), &TOLERANCE));
+
+        let (first, second) = arc.split(0.5f64).unwrap();
+        assert!(first.end_point().epsilon_equals(&second.start_point(), &TOLERANCE));
+        assert!(TOLERANCE.equals(arc.length(), first.length() + second.length()));
+        assert!(arc.split(FRAC_PI_2).is_none());
+    }
+
+    #[test]
+    pub fn to_nurbs()
+    {
+        let circle = Circle::from_center_normal_radius(Point3d::new(1f64, 2f64, 3f64), Vector3d::new(1f64, 1f64, 1f64), 3f64).unwrap();
+        let arc = Arc::new(circle, Interval::new(0.25f64, 4f64)).unwrap();
+        let curve = arc.to_nurbs().unwrap();
+
+        assert_eq!(2, curve.degree());
+        assert_eq!(7, curve.points().len());
+        assert!(curve.point_at(0.25f64).epsilon_equals(&arc.start_point(), &TOLERANCE));
+        assert!(curve.point_at(4f64).epsilon_equals(&arc.end_point(), &TOLERANCE));
+        for i in 0..=20 {
+            let point = curve.point_at(0.25f64 + 3.75f64 * i as f64 / 20f64);
+            assert!(TOLERANCE.equals(3f64, point.distance_to(&circle.center())));
+            assert!(TOLERANCE.equals(0f64, circle.plane.distance_to(&point)));
+        }
+    }
+
+}
@@ -1,18 +1,27 @@
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::IntervalPoint3d::IntervalPoint3d;
+use crate::geometry::basics::Line::Line;
 use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
 
 // A Box orientated to the WorldXY 
+#[derive(Copy, Clone)]
 pub struct BoundingBox {
-    /// 
+    /// The minimum corner
     min:Point3d,
-    /// 
+    /// The maximum corner
     max:Point3d,
 }
 
 impl BoundingBox {
 
-    /// A completely empty box with 0 volume
-    pub const EMPTY:BoundingBox = BoundingBox { min:Point3d::ORIGIN, max:Point3d::ORIGIN };
+    /// A completely empty box with 0 volume, which contains nothing.
+    /// Growing it to include anything gives a box around just that
+    pub const EMPTY:BoundingBox = BoundingBox { min:Point3d::INFINITY, max:Point3d::NEGATIVE_INFINITY };
 
     // An unset box with an unset min/max
     pub const UNSET:BoundingBox = BoundingBox { min:Point3d::UNSET, max:Point3d::UNSET };
@@ -22,51 +31,207 @@ impl BoundingBox {
         BoundingBox { min, max }
     }
 
+    /// Creates the smallest box containing all the points, or an empty box if there are none
+    pub fn from_points<I:IntoIterator<Item = Point3d>>(points:I) -> BoundingBox {
+        let mut bounds = BoundingBox::EMPTY;
+        for point in points {
+            bounds.grow_to_include(&point);
+        }
+
+        bounds
+    }
+
+    /// Returns the minimum corner
+    pub fn min(&self) -> Point3d {
+        self.min
+    }
+
+    /// Returns the maximum corner
+    pub fn max(&self) -> Point3d {
+        self.max
+    }
+
+    /// Returns true if the box contains nothing, as when it is EMPTY or two disjoint boxes were intersected
+    pub fn is_empty(&self) -> bool {
+        !(self.min.x <= self.max.x && self.min.y <= self.max.y && self.min.z <= self.max.z)
+    }
+
+    /// Returns the vector from the minimum to the maximum corner
+    pub fn diagonal(&self) -> Vector3d {
+        if self.is_empty() {
+            return Vector3d::ORIGIN;
+        }
+
+        self.max - self.min
+    }
+
     /// Returns the volume of the bounding box
-    pub fn volume(&self) -> f32 {
-        let x_dist = self.min.x - self.min.x;
-        let y_dist = self.min.z - self.min.y;
+    pub fn volume(&self) -> f64 {
+        if self.is_empty() {
+            return 0f64;
+        }
+
+        let x_dist = self.max.x - self.min.x;
+        let y_dist = self.max.y - self.min.y;
         let z_dist = self.max.z - self.min.z;
         
         x_dist * y_dist * z_dist
     }
 
+    /// Returns the area of the six faces of the bounding box
+    pub fn area(&self) -> f64 {
+        let size = self.diagonal();
+        2f64 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    /// Classifies the box by how many of its sides are no longer than the tolerance
+    pub fn is_degenerate(&self, tolerance:&Tolerance) -> Degeneracy {
+        if !self.is_valid() {
+            return Degeneracy::Invalid;
+        }
+
+        let size = self.diagonal();
+        let flat = [size.x, size.y, size.z].iter().filter(|side| **side <= tolerance.absolute).count();
+        match flat {
+            0 => Degeneracy::Box,
+            1 => Degeneracy::Plane,
+            2 => Degeneracy::Line,
+            _ => Degeneracy::Point,
+        }
+    }
+
     /// The center of the Box
     pub fn center(&self) -> Point3d {
-        let x_half = self.min.x + ((self.max.x - self.min.x) / 2f32);
-        let y_half = self.min.y + ((self.max.y - self.min.y) / 2f32);
-        let z_half = self.min.z + ((self.max.z - self.min.z) / 2f32);
+        let x_half = self.min.x + ((self.max.x - self.min.x) / 2f64);
+        let y_half = self.min.y + ((self.max.y - self.min.y) / 2f64);
+        let z_half = self.min.z + ((self.max.z - self.min.z) / 2f64);
 
         Point3d::new(x_half, y_half, z_half)
     }
 
-    pub fn contains_point(&self, point:Point3d) -> bool {
-        if point.x < self.min.x || point.x > self.max.x {
+    /// Returns true if the point is inside the box, or within tolerance of its faces.
+    /// An empty or unset box contains nothing
+    pub fn contains_point(&self, point:Point3d, tolerance:&Tolerance) -> bool {
+        if self.is_empty() {
             return false;
         }
-        if point.y < self.min.y || point.y > self.max.y {
+
+        let outside = |value:f64, min:f64, max:f64| {
+            value < min - tolerance.allowance(min) || value > max + tolerance.allowance(max)
+        };
+
+        if outside(point.x, self.min.x, self.max.x) {
             return false;
         }
-        if point.z < self.min.z || point.z > self.max.z {
+        if outside(point.y, self.min.y, self.max.y) {
+            return false;
+        }
+        if outside(point.z, self.min.z, self.max.z) {
             return false;
         }
 
-        return true
+        true
     }
 
-    pub fn contains_boundingbox(&self, bounds:BoundingBox) -> bool {
-        self.contains_point(bounds.min) && self.contains_point(bounds.max)
+    /// Returns whether a point known only within rounding bounds is certainly inside the box, faces included,
+    /// or certainly outside it. Returns None when the bounds straddle a face and the answer could be either
+    pub fn contains_point_certified(&self, point:&IntervalPoint3d) -> Option<bool> {
+        let coordinates = [(point.x, self.min.x, self.max.x), (point.y, self.min.y, self.max.y), (point.z, self.min.z, self.max.z)];
+
+        if coordinates.iter().any(|(value, min, max)| value.upper() < *min || value.lower() > *max) {
+            return Some(false);
+        }
+        if coordinates.iter().all(|(value, min, max)| value.lower() >= *min && value.upper() <= *max) {
+            return Some(true);
+        }
+
+        None
+    }
+
+    /// Returns true if the bounds are inside the box, or within tolerance of its faces
+    pub fn contains_boundingbox(&self, bounds:BoundingBox, tolerance:&Tolerance) -> bool {
+        self.contains_point(bounds.min, tolerance) && self.contains_point(bounds.max, tolerance)
+    }
+
+    /// Returns the closest point on or inside the box, or UNSET if the box is empty or unset
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        if self.is_empty() {
+            return Point3d::UNSET;
+        }
+
+        Point3d::new(point.x.clamp(self.min.x, self.max.x),
+                     point.y.clamp(self.min.y, self.max.y),
+                     point.z.clamp(self.min.z, self.max.z))
     }
 
-    pub fn inflate_uniform(&self, val:f32) {
+    /// Returns the distance from the point to the box, which is 0 anywhere inside.
+    /// An empty or unset box is infinitely far away
+    pub fn distance_to(&self, point:&Point3d) -> f64 {
+        if self.is_empty() {
+            return f64::INFINITY;
+        }
+
+        point.distance_to(&self.closest_point(point))
+    }
+
+    /// Grows the box so it contains the point.
+    /// An EMPTY or UNSET box becomes a box around just the point
+    pub fn grow_to_include(&mut self, point:&Point3d) {
+        // f64::min and f64::max ignore NaN, so an unset box takes the point's coordinates
+        self.min = Point3d::new(self.min.x.min(point.x), self.min.y.min(point.y), self.min.z.min(point.z));
+        self.max = Point3d::new(self.max.x.max(point.x), self.max.y.max(point.y), self.max.z.max(point.z));
+    }
+
+    /// Grows the box so it contains the other box.
+    /// Growing to include an EMPTY box changes nothing
+    pub fn grow_to_include_box(&mut self, bounds:&BoundingBox) {
+        if bounds.is_empty() {
+            return;
+        }
+
+        self.grow_to_include(&bounds.min);
+        self.grow_to_include(&bounds.max);
+    }
+
+    /// Returns the smallest box containing both boxes
+    pub fn union(&self, other:&BoundingBox) -> BoundingBox {
+        let mut bounds = *self;
+        bounds.grow_to_include_box(other);
+        bounds
+    }
+
+    /// Returns the box both boxes share, or EMPTY if they do not touch
+    pub fn intersection(&self, other:&BoundingBox) -> BoundingBox {
+        let bounds = BoundingBox::new(Point3d::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y), self.min.z.max(other.min.z)),
+                                      Point3d::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y), self.max.z.min(other.max.z)));
+        if bounds.is_empty() {
+            return BoundingBox::EMPTY;
+        }
+
+        bounds
+    }
+
+    /// Grows the box by the same amount on every side, a negative amount shrinks it
+    pub fn inflate_uniform(&mut self, val:f64) {
         self.inflate(val, val, val)
     }
 
-    pub fn inflate(&self, x:f32, y:f32, z:f32) {
-        panic!("Not implemented!")
+    /// Grows the box by `x`, `y` and `z` on either side along each axis, negative amounts shrink it.
+    /// A box shrunk past its center becomes EMPTY
+    pub fn inflate(&mut self, x:f64, y:f64, z:f64) {
+        if self.is_empty() {
+            return;
+        }
+
+        let amount = Vector3d::new(x, y, z);
+        self.min = self.min - amount;
+        self.max = self.max + amount;
+        if self.is_empty() {
+            *self = BoundingBox::EMPTY;
+        }
     }
 
-    pub fn point_at(&self, x:f32, y:f32, z:f32) -> Point3d {
+    pub fn point_at(&self, x:f64, y:f64, z:f64) -> Point3d {
         let x_diff = self.max.x - self.min.x;
         let rel_x = x_diff * x;
         let new_x = self.min.x + rel_x;
@@ -85,25 +250,93 @@ impl BoundingBox {
     /// Returns all the corners of the Box
     pub fn get_corners(&self) -> Vec<Point3d> {
         vec![
-            self.point_at(0f32, 0f32, 0f32),
-            self.point_at(0f32, 1f32, 0f32),
-            self.point_at(0f32, 0f32, 1f32),
-            self.point_at(0f32, 1f32, 1f32),
-
-            self.point_at(1f32, 0f32, 0f32),
-            self.point_at(1f32, 1f32, 0f32),
-            self.point_at(1f32, 0f32, 1f32),
-            self.point_at(1f32, 1f32, 1f32),
+            self.point_at(0f64, 0f64, 0f64),
+            self.point_at(0f64, 1f64, 0f64),
+            self.point_at(0f64, 0f64, 1f64),
+            self.point_at(0f64, 1f64, 1f64),
+
+            self.point_at(1f64, 0f64, 0f64),
+            self.point_at(1f64, 1f64, 0f64),
+            self.point_at(1f64, 0f64, 1f64),
+            self.point_at(1f64, 1f64, 1f64),
         ]
         
     }
 
+    /// Returns the twelve edges of the box, each running from a corner towards the maximum
+    pub fn edges(&self) -> [Line; 12] {
+        let corners = self.get_corners();
+        let mut edges = [Line::UNSET; 12];
+        let mut count = 0;
+
+        // The corners are ordered so each bit of their index picks the minimum or maximum along one axis
+        for (i, corner) in corners.iter().enumerate() {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    edges[count] = Line::new(*corner, corners[i | bit]);
+                    count += 1;
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Returns the tight box around the transformed corners, leaving this box unchanged
+    pub fn transformed(&self, xform:&Transform) -> Result<BoundingBox, TransformError> {
+        let mut bounds = *self;
+        bounds.transform(xform)?;
+        Ok(bounds)
+    }
+
+}
+
+/// How many dimensions a bounding box has collapsed by
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Degeneracy {
+    /// Every side is longer than the tolerance
+    Box,
+    /// One side is flat, so the box is a rectangle
+    Plane,
+    /// Two sides are flat, so the box is a line
+    Line,
+    /// Every side is flat
+    Point,
+    /// The box is unset or empty
+    Invalid,
 }
 
 impl IsValid for BoundingBox {
+    /// A valid box may be flat along any axis, use `is_degenerate` to find out
     fn is_valid(&self) -> bool {
         self.min.is_valid() && self.max.is_valid() &&
-        self.max > self.min
+        !self.is_empty()
+    }
+}
+
+impl Transformable for BoundingBox {
+    /// Transforms the corners of the box and resizes it to enclose them.
+    /// An EMPTY box stays empty
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let mut bounds = BoundingBox::EMPTY;
+        for mut corner in self.get_corners() {
+            corner.transform(xform)?;
+            bounds.grow_to_include(&corner);
+        }
+
+        *self = bounds;
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for BoundingBox {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.min.epsilon_equals(&other.min, tolerance) &&
+        self.max.epsilon_equals(&other.max, tolerance)
     }
 }
 
@@ -112,8 +345,128 @@ impl PartialEq for BoundingBox {
         self.min.eq(&other.min) &&
         self.max.eq(&other.max)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn contains_point_on_face() {
+        let bounds = BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 1f64));
+        let nearly_on_face = Point3d::new(0.1f64 * 3f64 + 0.7f64, 0.5f64, 0.5f64);
+
+        assert!(bounds.contains_point(nearly_on_face, &Tolerance::DEFAULT));
+        assert!(bounds.contains_point(Point3d::new(1.0005f64, 0.5f64, 0.5f64), &Tolerance::DEFAULT));
+        assert!(!bounds.contains_point(Point3d::new(1.0005f64, 0.5f64, 0.5f64), &Tolerance::EXACT));
+        assert!(!BoundingBox::EMPTY.contains_point(Point3d::ORIGIN, &Tolerance::DEFAULT));
+        assert!(!BoundingBox::UNSET.contains_point(Point3d::ORIGIN, &Tolerance::DEFAULT));
+        assert!(!BoundingBox::EMPTY.contains_boundingbox(bounds, &Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn contains_point_certified() {
+        use crate::geometry::basics::IntervalScalar::IntervalScalar;
+
+        let bounds = BoundingBox::new(Point3d::ORIGIN, Point3d::new(0.3f64, 1f64, 1f64));
+        let inside = IntervalPoint3d::from(Point3d::new(0.1f64, 0.5f64, 1f64));
+        let outside = IntervalPoint3d::from(Point3d::new(0.1f64, 2f64, 0.5f64));
+
+        // 0.1 * 3 rounds above 0.3, and its rounding error reaches back down to the face
+        let uncertain = IntervalScalar::exact(0.1f64) * IntervalScalar::exact(3f64);
+        let on_face = IntervalPoint3d::new(uncertain, 0.5f64.into(), 0.5f64.into());
+
+        assert_eq!(Some(true), bounds.contains_point_certified(&inside));
+        assert_eq!(Some(false), bounds.contains_point_certified(&outside));
+        assert_eq!(None, bounds.contains_point_certified(&on_face));
+    }
+
+    #[test]
+    pub fn from_points_and_growth() {
+        let points = vec![Point3d::new(1f64, -2f64, 0f64), Point3d::new(-1f64, 3f64, 2f64), Point3d::new(0f64, 0f64, 5f64)];
+        let bounds = BoundingBox::from_points(points);
+
+        assert!(bounds.min() == Point3d::new(-1f64, -2f64, 0f64));
+        assert!(bounds.max() == Point3d::new(1f64, 3f64, 5f64));
+        assert!(BoundingBox::from_points(Vec::new()).is_empty());
+
+        let mut unset = BoundingBox::UNSET;
+        unset.grow_to_include(&Point3d::ORIGIN);
+        assert!(unset.is_valid());
+        assert_eq!(Degeneracy::Point, unset.is_degenerate(&Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn union_and_intersection() {
+        let a = BoundingBox::new(Point3d::ORIGIN, Point3d::new(2f64, 2f64, 2f64));
+        let b = BoundingBox::new(Point3d::new(1f64, 1f64, 1f64), Point3d::new(3f64, 4f64, 5f64));
+        let far = BoundingBox::new(Point3d::new(10f64, 10f64, 10f64), Point3d::new(11f64, 11f64, 11f64));
 
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
+        assert!(a.union(&b) == BoundingBox::new(Point3d::ORIGIN, Point3d::new(3f64, 4f64, 5f64)));
+        assert!(a.intersection(&b) == BoundingBox::new(Point3d::new(1f64, 1f64, 1f64), Point3d::new(2f64, 2f64, 2f64)));
+        assert!(a.intersection(&far).is_empty());
+        assert!(!a.intersection(&far).is_valid());
+        assert!(a.union(&BoundingBox::EMPTY) == a);
+        assert_eq!(0f64, BoundingBox::EMPTY.volume());
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn inflate() {
+        let mut bounds = BoundingBox::new(Point3d::ORIGIN, Point3d::new(2f64, 2f64, 2f64));
+        bounds.inflate(1f64, 0f64, -0.5f64);
+        assert!(bounds == BoundingBox::new(Point3d::new(-1f64, 0f64, 0.5f64), Point3d::new(3f64, 2f64, 1.5f64)));
+
+        bounds.inflate_uniform(-3f64);
+        assert!(bounds.is_empty());
+    }
+
+    #[test]
+    pub fn measurements() {
+        let bounds = BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 2f64, 3f64));
+
+        assert!(bounds.diagonal() == Vector3d::new(1f64, 2f64, 3f64));
+        assert_eq!(6f64, bounds.volume());
+        assert_eq!(22f64, bounds.area());
+        assert_eq!(12, bounds.edges().len());
+        assert_eq!(24f64, bounds.edges().iter().map(|edge| edge.length()).sum::<f64>());
+    }
+
+    #[test]
+    pub fn is_degenerate() {
+        let tolerance = Tolerance::DEFAULT;
+        let flat = BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 0.0001f64));
+        let line = BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 0f64, 0f64));
+
+        assert_eq!(Degeneracy::Box, BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 1f64)).is_degenerate(&tolerance));
+        assert_eq!(Degeneracy::Plane, flat.is_degenerate(&tolerance));
+        assert_eq!(Degeneracy::Box, flat.is_degenerate(&Tolerance::EXACT));
+        assert_eq!(Degeneracy::Line, line.is_degenerate(&tolerance));
+        assert_eq!(Degeneracy::Invalid, BoundingBox::UNSET.is_degenerate(&tolerance));
+    }
+
+    #[test]
+    pub fn closest_point_and_distance() {
+        let bounds = BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 1f64));
+
+        assert!(bounds.closest_point(&Point3d::new(2f64, 0.5f64, -1f64)) == Point3d::new(1f64, 0.5f64, 0f64));
+        assert_eq!(0f64, bounds.distance_to(&Point3d::new(0.5f64, 0.5f64, 0.5f64)));
+        assert_eq!(5f64, bounds.distance_to(&Point3d::new(4f64, 5f64, 0.5f64)));
+
+        assert!(!BoundingBox::EMPTY.closest_point(&Point3d::ORIGIN).is_valid());
+        assert!(!BoundingBox::UNSET.closest_point(&Point3d::ORIGIN).is_valid());
+        assert_eq!(f64::INFINITY, BoundingBox::EMPTY.distance_to(&Point3d::ORIGIN));
+    }
+
+    #[test]
+    pub fn transformed() {
+        let bounds = BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 1f64));
+        let rotation = Transform::rotation(std::f64::consts::FRAC_PI_4, &Vector3d::ZAXIS, &Point3d::ORIGIN);
+        let rotated = bounds.transformed(&rotation).unwrap();
+        let half_root = 0.5f64.sqrt();
+
+        assert!(rotated.min().epsilon_equals(&Point3d::new(-half_root, 0f64, 0f64), &Tolerance::DEFAULT));
+        assert!(rotated.max().epsilon_equals(&Point3d::new(half_root, 2f64 * half_root, 1f64), &Tolerance::DEFAULT));
+        assert!(bounds == BoundingBox::new(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 1f64)));
+    }
+
+}
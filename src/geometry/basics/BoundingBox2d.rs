@@ -0,0 +1,221 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Point2d::Point2d;
+use crate::geometry::basics::Vector2d::Vector2d;
+
+// A Rectangle orientated to the X and Y axes
+#[derive(Copy, Clone)]
+pub struct BoundingBox2d {
+    /// The minimum corner
+    min:Point2d,
+    /// The maximum corner
+    max:Point2d,
+}
+
+impl BoundingBox2d {
+
+    /// A completely empty box with 0 area, which contains nothing.
+    /// Growing it to include anything gives a box around just that
+    pub const EMPTY:BoundingBox2d = BoundingBox2d { min:Point2d::INFINITY, max:Point2d::NEGATIVE_INFINITY };
+
+    // An unset box with an unset min/max
+    pub const UNSET:BoundingBox2d = BoundingBox2d { min:Point2d::UNSET, max:Point2d::UNSET };
+
+    /// Creates a new boundingbox between the two points
+    pub fn new(min:Point2d, max:Point2d) -> BoundingBox2d {
+        BoundingBox2d { min, max }
+    }
+
+    /// Creates the smallest box containing all the points, or an empty box if there are none
+    pub fn from_points<I:IntoIterator<Item = Point2d>>(points:I) -> BoundingBox2d {
+        let mut bounds = BoundingBox2d::EMPTY;
+        for point in points {
+            bounds.grow_to_include(&point);
+        }
+
+        bounds
+    }
+
+    /// Returns the minimum corner
+    pub fn min(&self) -> Point2d {
+        self.min
+    }
+
+    /// Returns the maximum corner
+    pub fn max(&self) -> Point2d {
+        self.max
+    }
+
+    /// Returns true if the box contains nothing, as when it is EMPTY or two disjoint boxes were intersected
+    pub fn is_empty(&self) -> bool {
+        !(self.min.x <= self.max.x && self.min.y <= self.max.y)
+    }
+
+    /// Returns the vector from the minimum to the maximum corner
+    pub fn diagonal(&self) -> Vector2d {
+        if self.is_empty() {
+            return Vector2d::ORIGIN;
+        }
+
+        self.max - self.min
+    }
+
+    /// Returns the area of the box
+    pub fn area(&self) -> f64 {
+        let size = self.diagonal();
+        size.x * size.y
+    }
+
+    /// The center of the box
+    pub fn center(&self) -> Point2d {
+        self.min.interpolate(&self.max, 0.5f64)
+    }
+
+    /// Returns the point at the normalized parameters, 0 being the minimum and 1 the maximum along each axis
+    pub fn point_at(&self, x:f64, y:f64) -> Point2d {
+        Point2d::new(self.min.x + (self.max.x - self.min.x) * x,
+                     self.min.y + (self.max.y - self.min.y) * y)
+    }
+
+    /// Returns the four corners, anticlockwise from the minimum
+    pub fn corners(&self) -> [Point2d; 4] {
+        [
+            self.point_at(0f64, 0f64),
+            self.point_at(1f64, 0f64),
+            self.point_at(1f64, 1f64),
+            self.point_at(0f64, 1f64),
+        ]
+    }
+
+    /// Returns true if the point is inside the box, or within tolerance of its edges
+    pub fn contains_point(&self, point:&Point2d, tolerance:&Tolerance) -> bool {
+        let inside = |value:f64, min:f64, max:f64| {
+            value >= min - tolerance.allowance(min) && value <= max + tolerance.allowance(max)
+        };
+
+        inside(point.x, self.min.x, self.max.x) &&
+        inside(point.y, self.min.y, self.max.y)
+    }
+
+    /// Returns the closest point on or inside the box, or UNSET if the box is empty or unset
+    pub fn closest_point(&self, point:&Point2d) -> Point2d {
+        if self.is_empty() {
+            return Point2d::UNSET;
+        }
+
+        Point2d::new(point.x.clamp(self.min.x, self.max.x),
+                     point.y.clamp(self.min.y, self.max.y))
+    }
+
+    /// Grows the box so it contains the point.
+    /// An EMPTY or UNSET box becomes a box around just the point
+    pub fn grow_to_include(&mut self, point:&Point2d) {
+        // f64::min and f64::max ignore NaN, so an unset box takes the point's coordinates
+        self.min = Point2d::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point2d::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    /// Returns the smallest box containing both boxes
+    pub fn union(&self, other:&BoundingBox2d) -> BoundingBox2d {
+        if other.is_empty() {
+            return *self;
+        }
+
+        let mut bounds = *self;
+        bounds.grow_to_include(&other.min);
+        bounds.grow_to_include(&other.max);
+        bounds
+    }
+
+    /// Returns the box both boxes share, or EMPTY if they do not touch
+    pub fn intersection(&self, other:&BoundingBox2d) -> BoundingBox2d {
+        let bounds = BoundingBox2d::new(Point2d::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y)),
+                                        Point2d::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y)));
+        if bounds.is_empty() {
+            return BoundingBox2d::EMPTY;
+        }
+
+        bounds
+    }
+
+    /// Grows the box by `x` and `y` on either side along each axis, negative amounts shrink it.
+    /// A box shrunk past its center becomes EMPTY
+    pub fn inflate(&mut self, x:f64, y:f64) {
+        if self.is_empty() {
+            return;
+        }
+
+        let amount = Vector2d::new(x, y);
+        self.min = self.min - amount;
+        self.max = self.max + amount;
+        if self.is_empty() {
+            *self = BoundingBox2d::EMPTY;
+        }
+    }
+
+}
+
+impl IsValid for BoundingBox2d {
+    /// A valid box may be flat along either axis
+    fn is_valid(&self) -> bool {
+        self.min.is_valid() && self.max.is_valid() &&
+        !self.is_empty()
+    }
+}
+
+impl EpsilonEquals for BoundingBox2d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.min.epsilon_equals(&other.min, tolerance) &&
+        self.max.epsilon_equals(&other.max, tolerance)
+    }
+}
+
+impl PartialEq for BoundingBox2d {
+    fn eq(&self, other: &Self) -> bool {
+        self.min.eq(&other.min) &&
+        self.max.eq(&other.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn growth_and_measurements() {
+        let bounds = BoundingBox2d::from_points(vec![Point2d::new(1f64, -1f64), Point2d::new(-2f64, 3f64)]);
+
+        assert!(bounds.min() == Point2d::new(-2f64, -1f64));
+        assert_eq!(12f64, bounds.area());
+        assert!(bounds.center() == Point2d::new(-0.5f64, 1f64));
+        assert!(bounds.corners()[2] == bounds.max());
+        assert!(BoundingBox2d::from_points(Vec::new()).is_empty());
+        assert!(!BoundingBox2d::UNSET.is_valid());
+    }
+
+    #[test]
+    pub fn union_and_intersection() {
+        let a = BoundingBox2d::new(Point2d::ORIGIN, Point2d::new(2f64, 2f64));
+        let b = BoundingBox2d::new(Point2d::new(1f64, 1f64), Point2d::new(3f64, 3f64));
+
+        assert!(a.union(&b) == BoundingBox2d::new(Point2d::ORIGIN, Point2d::new(3f64, 3f64)));
+        assert!(a.intersection(&b) == BoundingBox2d::new(Point2d::new(1f64, 1f64), Point2d::new(2f64, 2f64)));
+        assert!(a.intersection(&BoundingBox2d::new(Point2d::new(5f64, 5f64), Point2d::new(6f64, 6f64))).is_empty());
+    }
+
+    #[test]
+    pub fn containment() {
+        let mut bounds = BoundingBox2d::new(Point2d::ORIGIN, Point2d::new(1f64, 1f64));
+
+        assert!(bounds.contains_point(&Point2d::new(1.0005f64, 0.5f64), &Tolerance::DEFAULT));
+        assert!(!bounds.contains_point(&Point2d::new(1.0005f64, 0.5f64), &Tolerance::EXACT));
+        assert!(bounds.closest_point(&Point2d::new(3f64, -1f64)) == Point2d::new(1f64, 0f64));
+        assert!(!BoundingBox2d::EMPTY.closest_point(&Point2d::ORIGIN).is_valid());
+        assert!(!BoundingBox2d::UNSET.closest_point(&Point2d::ORIGIN).is_valid());
+
+        bounds.inflate(1f64, -0.25f64);
+        assert!(bounds == BoundingBox2d::new(Point2d::new(-1f64, 0.25f64), Point2d::new(2f64, 0.75f64)));
+    }
+
+}
@@ -1,22 +1,348 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::BoundingBox::BoundingBox;
+use crate::geometry::basics::Interval::Interval;
+use crate::geometry::basics::IntervalPoint3d::IntervalPoint3d;
+use crate::geometry::basics::Mesh::Mesh;
 use crate::geometry::basics::Point3d::Point3d;
 use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
 
-// A Box 
+/// A Box aligned to a plane, spanning an interval along each of the plane's axes
+#[derive(Copy, Clone)]
 pub struct Box {
+    /// The plane the box is aligned to
     plane:Plane,
-    min:Point3d,
-    max:Point3d
+    /// The extent along the plane's X axis
+    x:Interval,
+    /// The extent along the plane's Y axis
+    y:Interval,
+    /// The extent along the plane's Z axis
+    z:Interval,
 }
 
+impl Box {
+
+    pub const UNSET:Box = Box { plane:Plane::UNSET, x:Interval::UNSET, y:Interval::UNSET, z:Interval::UNSET };
+
+    /// Creates a new box on the plane, the intervals are measured from the plane origin along each axis
+    pub fn new(plane:Plane, x:Interval, y:Interval, z:Interval) -> Box {
+        Box { plane, x, y, z }
+    }
+
+    /// Creates a box on the World XY plane matching the bounding box
+    pub fn from_bounding_box(bounds:&BoundingBox) -> Box {
+        let (min, max) = (bounds.min(), bounds.max());
+
+        Box::new(Plane::WORLDXY,
+                 Interval::new(min.x, max.x),
+                 Interval::new(min.y, max.y),
+                 Interval::new(min.z, max.z))
+    }
+
+    /// Returns the plane the box is aligned to
+    pub fn plane(&self) -> Plane {
+        self.plane
+    }
+
+    /// Returns the extent along the plane's X axis
+    pub fn x(&self) -> Interval {
+        self.x
+    }
+
+    /// Returns the extent along the plane's Y axis
+    pub fn y(&self) -> Interval {
+        self.y
+    }
+
+    /// Returns the extent along the plane's Z axis
+    pub fn z(&self) -> Interval {
+        self.z
+    }
+
+    /// Returns the point at the normalized parameters, 0 being the start of each interval and 1 the end
+    pub fn point_at(&self, x:f64, y:f64, z:f64) -> Point3d {
+        self.plane.point_at(self.x.parameter_at(x), self.y.parameter_at(y), self.z.parameter_at(z))
+    }
+
+    /// Returns the corners of the box.
+    /// The bottom four come first, anticlockwise about the plane's Z axis from the start of each interval, then the top four
+    pub fn corners(&self) -> [Point3d; 8] {
+        [
+            self.point_at(0f64, 0f64, 0f64),
+            self.point_at(1f64, 0f64, 0f64),
+            self.point_at(1f64, 1f64, 0f64),
+            self.point_at(0f64, 1f64, 0f64),
+
+            self.point_at(0f64, 0f64, 1f64),
+            self.point_at(1f64, 0f64, 1f64),
+            self.point_at(1f64, 1f64, 1f64),
+            self.point_at(0f64, 1f64, 1f64),
+        ]
+    }
+
+    /// Returns the center of the box
+    pub fn center(&self) -> Point3d {
+        self.plane.point_at(self.x.mid(), self.y.mid(), self.z.mid())
+    }
+
+    /// Returns the volume of the box
+    pub fn volume(&self) -> f64 {
+        (self.x.length() * self.y.length() * self.z.length()).abs()
+    }
+
+    /// Returns the area of the six faces of the box
+    pub fn area(&self) -> f64 {
+        let (x, y, z) = (self.x.length().abs(), self.y.length().abs(), self.z.length().abs());
+        2f64 * (x * y + y * z + z * x)
+    }
+
+    /// Returns true if the point is inside the box, or within tolerance of its faces.
+    /// The point is measured in the box's plane, so the tolerance is the same for every face
+    pub fn contains_point(&self, point:&Point3d, tolerance:&Tolerance) -> bool {
+        let local = self.plane.remap_to_plane_space(point);
+
+        self.x.includes_parameter(local.x, tolerance) &&
+        self.y.includes_parameter(local.y, tolerance) &&
+        self.z.includes_parameter(local.z, tolerance)
+    }
+
+    /// Returns whether the point is certainly inside the box, faces included, or certainly outside it.
+    /// The point is measured in the box's plane with interval arithmetic, and None is returned when
+    /// the rounding error leaves it unclear which side of a face the point is on
+    pub fn contains_point_certified(&self, point:&Point3d) -> Option<bool> {
+        let offset = IntervalPoint3d::from(*point) - IntervalPoint3d::from(self.plane.origin);
+        let local = [
+            (offset.dot(&self.plane.x.into()), self.x),
+            (offset.dot(&self.plane.y.into()), self.y),
+            (offset.dot(&self.plane.z.into()), self.z),
+        ];
+
+        if local.iter().any(|(value, interval)| value.upper() < interval.min() || value.lower() > interval.max()) {
+            return Some(false);
+        }
+        if local.iter().all(|(value, interval)| value.lower() >= interval.min() && value.upper() <= interval.max()) {
+            return Some(true);
+        }
+
+        None
+    }
+
+    /// Returns the closest point on or inside the box, or UNSET if the box is not valid
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        if !self.is_valid() {
+            return Point3d::UNSET;
+        }
+
+        let local = self.plane.remap_to_plane_space(point);
+        let clamp = |value:f64, interval:&Interval| value.clamp(interval.min(), interval.max());
+
+        self.plane.point_at(clamp(local.x, &self.x), clamp(local.y, &self.y), clamp(local.z, &self.z))
+    }
+
+    /// Returns the smallest world aligned box containing the box
+    pub fn bounding_box(&self) -> BoundingBox {
+        // Along each world axis the box reaches half of each side, scaled by how much that side leans along the axis
+        let half = [self.x.length().abs() / 2f64, self.y.length().abs() / 2f64, self.z.length().abs() / 2f64];
+        let reach = |x:f64, y:f64, z:f64| x.abs() * half[0] + y.abs() * half[1] + z.abs() * half[2];
+        let (x, y, z) = (self.plane.x, self.plane.y, self.plane.z);
+        let extent = Vector3d::new(reach(x.x, y.x, z.x), reach(x.y, y.y, z.y), reach(x.z, y.z, z.z));
+
+        BoundingBox::new(self.center() - extent, self.center() + extent)
+    }
+
+    /// Returns a mesh of the six faces of the box as quads, each facing outwards
+    pub fn to_mesh(&self) -> Mesh {
+        let faces = vec![
+            [0, 3, 2, 1],
+            [4, 5, 6, 7],
+            [0, 1, 5, 4],
+            [1, 2, 6, 5],
+            [2, 3, 7, 6],
+            [3, 0, 4, 7],
+        ];
+
+        Mesh::new(self.corners().to_vec(), faces)
+    }
+
+}
+
+impl IsValid for Box {
+    fn is_valid(&self) -> bool {
+        self.plane.is_valid() &&
+        [self.x, self.y, self.z].iter().all(|interval| interval.is_valid() && interval.is_increasing())
+    }
+
+    /// A box is only valid if every side is longer than the tolerance
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() &&
+        [self.x, self.y, self.z].iter().all(|interval| interval.length() > tolerance.absolute)
+    }
+}
+
+impl Transformable for Box {
+    /// Transforms the plane of the box, scaling the extents by how much each axis is stretched.
+    /// Fails when the box would be sheared, as it could no longer be described by a plane.
+    /// A mirrored box keeps a right-handed plane, so its Z extent is flipped to match
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        let mut axes = [self.plane.x, self.plane.y, self.plane.z];
+        for axis in axes.iter_mut() {
+            axis.transform(xform)?;
+        }
+
+        let mut plane = self.plane;
+        plane.transform(xform)?;
+
+        let x = self.x * axes[0].length();
+        let y = self.y * axes[1].length();
+        let mut z = self.z * axes[2].length();
+        if axes[2].dot(&plane.z) < 0f64 {
+            z = (z * -1f64).swap();
+        }
+
+        *self = Box::new(plane, x, y, z);
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Box {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.x.epsilon_equals(&other.x, tolerance) &&
+        self.y.epsilon_equals(&other.y, tolerance) &&
+        self.z.epsilon_equals(&other.z, tolerance) &&
+        self.plane.epsilon_equals(&other.plane, tolerance)
+    }
+}
 
 impl PartialEq for Box {
     fn eq(&self, other: &Self) -> bool {
-        self.min.eq(&other.min) &&
-        self.max.eq(&other.max) &&
+        self.x.eq(&other.x) &&
+        self.y.eq(&other.y) &&
+        self.z.eq(&other.z) &&
         self.plane.eq(&other.plane)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_4;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
+
+    fn unit_box() -> Box {
+        let plane = Plane { origin:Point3d::new(1f64, 2f64, 3f64), ..Plane::WORLDXY };
+        Box::new(plane, Interval::new(0f64, 2f64), Interval::new(-1f64, 1f64), Interval::new(0f64, 3f64))
+    }
+
+    #[test]
+    pub fn is_valid() {
+        assert!(unit_box().is_valid());
+        assert!(!Box::UNSET.is_valid());
+
+        let flat = Box::new(Plane::WORLDXY, Interval::new(0f64, 1f64), Interval::new(0f64, 1f64), Interval::new(0f64, 0f64));
+        assert!(!flat.is_valid());
+    }
+
+    #[test]
+    pub fn measurements() {
+        let bx = unit_box();
+
+        assert_eq!(12f64, bx.volume());
+        assert_eq!(2f64 * (4f64 + 6f64 + 6f64), bx.area());
+        assert!(Point3d::new(2f64, 2f64, 4.5f64) == bx.center());
+        assert!(Point3d::new(1f64, 1f64, 3f64) == bx.corners()[0]);
+        assert!(Point3d::new(3f64, 3f64, 6f64) == bx.corners()[6]);
+    }
+
+    #[test]
+    pub fn from_bounding_box() {
+        let bounds = BoundingBox::new(Point3d::new(-1f64, 0f64, 2f64), Point3d::new(1f64, 4f64, 3f64));
+        let bx = Box::from_bounding_box(&bounds);
+
+        assert_eq!(8f64, bx.volume());
+        assert!(bx.bounding_box().epsilon_equals(&bounds, &TOLERANCE));
+    }
+
+    #[test]
+    pub fn contains_and_closest_point() {
+        let mut bx = unit_box();
+        bx.rotate(FRAC_PI_4, &Vector3d::ZAXIS, &Point3d::ORIGIN).unwrap();
+
+        let inside = bx.point_at(0.2f64, 0.9f64, 0.5f64);
+        assert!(bx.contains_point(&inside, &TOLERANCE));
+        assert!(bx.closest_point(&inside).epsilon_equals(&inside, &TOLERANCE));
+
+        let above = bx.point_at(0.5f64, 0.5f64, 1f64) + bx.plane().z * 2f64;
+        assert!(!bx.contains_point(&above, &TOLERANCE));
+        assert!(bx.closest_point(&above).epsilon_equals(&bx.point_at(0.5f64, 0.5f64, 1f64), &TOLERANCE));
+        assert!(!Box::UNSET.closest_point(&Point3d::ORIGIN).is_valid());
+    }
+
+    #[test]
+    pub fn contains_point_certified() {
+        let half_root = 0.5f64.sqrt();
+        let plane = Plane { origin:Point3d::ORIGIN,
+                            x:Vector3d::new(half_root, half_root, 0f64),
+                            y:Vector3d::new(-half_root, half_root, 0f64),
+                            z:Vector3d::ZAXIS };
+
+        // Rounded arithmetic puts the point just past the face, but its rounding error reaches back across it
+        let face = (0.1f64 * half_root + 0.1f64 * half_root).next_down();
+        let bx = Box::new(plane, Interval::new(0f64, face), Interval::new(-1f64, 1f64), Interval::new(0f64, 1f64));
+
+        assert_eq!(Some(true), bx.contains_point_certified(&bx.center()));
+        assert_eq!(Some(false), bx.contains_point_certified(&Point3d::new(0f64, 0f64, 2f64)));
+        assert_eq!(None, bx.contains_point_certified(&Point3d::new(0.1f64, 0.1f64, 0.5f64)));
+        assert!(!bx.contains_point(&Point3d::new(0.1f64, 0.1f64, 0.5f64), &Tolerance::EXACT));
+    }
+
+    #[test]
+    pub fn bounding_box_is_tight() {
+        let mut bx = unit_box();
+        bx.rotate(0.3f64, &Vector3d::new(1f64, 2f64, 3f64), &Point3d::ORIGIN).unwrap();
+        let bounds = bx.bounding_box();
 
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
+        let corners = bx.corners();
+        let lowest = corners.iter().map(|c| c.x).fold(f64::INFINITY, f64::min);
+        let highest = corners.iter().map(|c| c.z).fold(f64::NEG_INFINITY, f64::max);
+        assert!(TOLERANCE.equals(lowest, bounds.min().x));
+        assert!(TOLERANCE.equals(highest, bounds.max().z));
     }
+
+    #[test]
+    pub fn transform() {
+        let mut bx = unit_box();
+        bx.transform(&Transform::scale_non_uniform(&Plane::WORLDXY, 2f64, 1f64, 1f64)).unwrap();
+        assert!(TOLERANCE.equals(24f64, bx.volume()));
+
+        let mut mirrored = unit_box();
+        let corner = mirrored.point_at(1f64, 1f64, 1f64);
+        let mirror = Plane::from_point_normal(Point3d::ORIGIN, Vector3d::ZAXIS).unwrap();
+        mirrored.mirror(&mirror).unwrap();
+        assert!(mirrored.is_valid());
+        assert!(mirrored.contains_point(&Point3d::new(corner.x, corner.y, -corner.z), &TOLERANCE));
+
+        let mut sheared = unit_box();
+        let shear = Transform::shear(&Plane::WORLDXY, &Vector3d::new(1f64, 1f64, 0f64), &Vector3d::YAXIS, &Vector3d::ZAXIS);
+        assert_eq!(Err(TransformError::ShapeNotPreserved), sheared.transform(&shear));
+    }
+
+    #[test]
+    pub fn to_mesh() {
+        let bx = unit_box();
+        let mesh = bx.to_mesh();
+
+        assert!(mesh.is_valid());
+        assert!(TOLERANCE.equals(bx.area(), mesh.area()));
+        for i in 0..mesh.faces().len() {
+            let face = mesh.faces()[i];
+            let outward = mesh.vertices()[face[0]] - bx.center();
+            assert!(mesh.face_normal(i).dot(&outward) > 0f64);
+        }
+    }
+
 }
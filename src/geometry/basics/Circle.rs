@@ -1,86 +1,376 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::BoundingBox::BoundingBox;
+use crate::geometry::basics::Plane::{Plane, PlaneError};
 use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::PolyLine::PolyLine;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
 
-/// A Circle
+/// A Circle lying in a plane, centered on the plane origin.
+/// Angles are measured in radians from the plane's X axis towards its Y axis
+#[derive(Copy, Clone)]
 pub struct Circle
 {
-    /// The center of the circle
-    pub center: Point3d,
+    /// The plane of the circle, its origin is the center
+    pub plane: Plane,
 
     /// The radius of the circle
-    pub radius: f32,
+    pub radius: f64,
 
 }
 
 impl Circle {
-    
-    pub const UNSET:Circle = Circle { center:Point3d::UNSET, radius:f32::NAN };
-    
-    pub fn new(center:Point3d, radius: f32) -> Circle {
-        if radius.is_sign_negative() {
-            panic!("Input radius cannot be negative")
+
+    pub const UNSET:Circle = Circle { plane:Plane::UNSET, radius:f64::NAN };
+
+    /// Constructs a circle parallel to the World XY plane
+    pub fn new(center:Point3d, radius: f64) -> Result<Circle, CircleError> {
+        Circle::from_plane(Plane { origin:center, ..Plane::WORLDXY }, radius)
+    }
+
+    /// Constructs a circle on a plane, centered at the plane origin
+    pub fn from_plane(plane:Plane, radius: f64) -> Result<Circle, CircleError> {
+        if !(radius > 0f64 && radius.is_finite()) {
+            return Err(CircleError::InvalidRadius);
+        }
+
+        Ok(Circle { plane, radius })
+    }
+
+    /// Constructs a circle facing along `normal`
+    pub fn from_center_normal_radius(center:Point3d, normal:Vector3d, radius:f64) -> Result<Circle, CircleError> {
+        Circle::from_plane(Plane::from_point_normal(center, normal)?, radius)
+    }
+
+    /// Constructs the circle passing through all three points.
+    /// The plane's X axis points towards `a`, and the points run anticlockwise about its Z axis
+    pub fn from_three_points(a:Point3d, b:Point3d, c:Point3d) -> Result<Circle, CircleError> {
+        let ab = b - a;
+        let ac = c - a;
+        let normal = ab.cross(&ac);
+        let normal_squared = normal.dot(&normal);
+        if normal_squared == 0f64 || !normal_squared.is_finite() {
+            return Err(CircleError::Plane(PlaneError::Colinear));
+        }
+
+        // https://en.wikipedia.org/wiki/Circumcircle#Higher_dimensions
+        let offset = (ac.cross(&normal) * ab.dot(&ab) + normal.cross(&ab) * ac.dot(&ac)) / (2f64 * normal_squared);
+        let center = a + offset;
+        let to_a = a - center;
+
+        let plane = Plane::new(center, to_a, normal.cross(&to_a))?;
+        Circle::from_plane(plane, to_a.length())
+    }
+
+    // Methods
+
+    /// Returns the center of the circle
+    pub fn center(&self) -> Point3d {
+        self.plane.origin
+    }
+
+    /// Returns the direction the circle faces
+    pub fn normal(&self) -> Vector3d {
+        self.plane.z
+    }
+
+    /// Returns the length around the circle
+    pub fn circumference(&self) -> f64 {
+        2f64 * PI * self.radius
+    }
+
+    /// Returns the area enclosed by the circle
+    pub fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    /// Returns the point on the circle at `angle`
+    pub fn point_at(&self, angle:f64) -> Point3d {
+        let (sin, cos) = angle.sin_cos();
+        self.plane.origin + self.plane.x * (self.radius * cos) + self.plane.y * (self.radius * sin)
+    }
+
+    /// Returns the unit tangent at `angle`, pointing in the direction of increasing angle
+    pub fn tangent_at(&self, angle:f64) -> Vector3d {
+        let (sin, cos) = angle.sin_cos();
+        self.plane.y * cos - self.plane.x * sin
+    }
+
+    /// Returns the angle of the closest point on the circle, from 0 to 2 PI.
+    /// Returns None if the point is on the axis of the circle, where every point is equally close
+    pub fn closest_parameter(&self, point:&Point3d) -> Option<f64> {
+        let offset = *point - self.plane.origin;
+        let (u, v) = (offset.dot(&self.plane.x), offset.dot(&self.plane.y));
+        if u == 0f64 && v == 0f64 {
+            return None;
+        }
+
+        let angle = v.atan2(u);
+        Some(if angle < 0f64 { angle + 2f64 * PI } else { angle })
+    }
+
+    /// Returns the closest point on the circle.
+    /// Returns None if the point is on the axis of the circle, where every point is equally close
+    pub fn closest_point(&self, point:&Point3d) -> Option<Point3d> {
+        self.closest_parameter(point).map(|angle| self.point_at(angle))
+    }
+
+    /// Returns the smallest world aligned box containing the circle
+    pub fn bounding_box(&self) -> BoundingBox {
+        // Along each world axis the circle reaches radius * sin(angle between that axis and the normal)
+        let normal = Vector3d::unitize(&self.plane.z);
+        let reach = |n:f64| self.radius * (1f64 - n * n).max(0f64).sqrt();
+        let extent = Vector3d::new(reach(normal.x), reach(normal.y), reach(normal.z));
+
+        BoundingBox::new(self.center() - extent, self.center() + extent)
+    }
+
+    /// Returns a closed polyline through `segments` evenly spaced points on the circle.
+    /// The first point is repeated at the end to close it
+    pub fn to_polyline(&self, segments:usize) -> Result<PolyLine, CircleError> {
+        if segments < 3 {
+            return Err(CircleError::TooFewSegments);
         }
 
-        Circle { center, radius }
+        let step = 2f64 * PI / segments as f64;
+        let mut points:Vec<Point3d> = (0..segments).map(|i| self.point_at(step * i as f64)).collect();
+        points.push(points[0]);
+
+        Ok(PolyLine::new(points))
+    }
+
+}
+
+/// The reasons a Circle cannot be constructed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CircleError {
+    /// The radius is zero, negative or not finite
+    InvalidRadius,
+    /// No plane could be made for the circle
+    Plane(PlaneError),
+    /// A polyline needs at least three segments to approximate a circle
+    TooFewSegments,
+}
+
+impl From<PlaneError> for CircleError {
+    fn from(error: PlaneError) -> Self {
+        CircleError::Plane(error)
     }
+}
 
+impl fmt::Display for CircleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircleError::InvalidRadius => write!(f, "the radius must be positive and finite"),
+            CircleError::Plane(error) => write!(f, "the circle has no plane, {}", error),
+            CircleError::TooFewSegments => write!(f, "at least three segments are needed"),
+        }
+    }
 }
 
+impl std::error::Error for CircleError {}
+
 impl IsValid for Circle {
     fn is_valid(&self) -> bool {
-        self.center.is_valid() &&
+        self.plane.is_valid() &&
         self.radius.is_finite() &&
-        self.radius.is_sign_positive()
+        self.radius > 0f64
+    }
+
+    /// A circle is only valid if its radius is larger than the tolerance
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() && self.radius > tolerance.absolute
+    }
+}
+
+impl Transformable for Circle {
+    /// Transforms the plane and scales the radius.
+    /// The circle stays round only if its plane's X and Y axes are scaled equally and stay perpendicular,
+    /// anything else would produce an ellipse and is refused, use `Ellipse::from_transformed_circle` for those
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        if !xform.is_valid() {
+            return Err(TransformError::Invalid);
+        }
+        if !xform.is_affine() {
+            return Err(TransformError::ShapeNotPreserved);
+        }
+
+        let mut x = self.plane.x;
+        let mut y = self.plane.y;
+        x.transform(xform)?;
+        y.transform(xform)?;
+
+        let (x_scale, y_scale) = (x.length(), y.length());
+        if x_scale == 0f64 || y_scale == 0f64 {
+            return Err(TransformError::Degenerate);
+        }
+        if (x_scale - y_scale).abs() > Transform::TOLERANCE * x_scale ||
+           x.dot(&y).abs() > Transform::TOLERANCE * x_scale * y_scale {
+            return Err(TransformError::ShapeNotPreserved);
+        }
+
+        let mut center = self.plane.origin;
+        center.transform(xform)?;
+        let plane = Plane::new(center, x, y).map_err(|_| TransformError::Degenerate)?;
+
+        self.plane = plane;
+        self.radius *= x_scale;
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Circle {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.plane.epsilon_equals(&other.plane, tolerance) &&
+        tolerance.equals(self.radius, other.radius)
     }
 }
 
 impl PartialEq for Circle {
     fn eq(&self, other: &Self) -> bool {
-        self.center.eq(&other.center) &&
+        self.plane.eq(&other.plane) &&
         self.radius.eq(&other.radius)
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
-    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
 
     #[test]
-    #[should_panic]
     pub fn is_valid_failure_zero()
     {
-        let zero = Circle::new(Point3d::ORIGIN, 0f32);
-        assert!(!zero.is_valid());      
+        let zero = Circle::new(Point3d::ORIGIN, 0f64);
+        assert_eq!(Some(CircleError::InvalidRadius), zero.err());
     }
 
     #[test]
     pub fn is_valid_failure_unset()
     {
         let unset = Circle::UNSET;
-        assert!(!unset.is_valid()); 
+        assert!(!unset.is_valid());
     }
 
     #[test]
-    #[should_panic]
     pub fn is_valid_failure_negative()
     {
-        let negative = Circle::new(Point3d::ORIGIN, -100f32);
-        assert!(!negative.is_valid());      
+        let negative = Circle::new(Point3d::ORIGIN, -100f64);
+        assert_eq!(Some(CircleError::InvalidRadius), negative.err());
     }
 
     #[test]
     pub fn is_valid()
     {
-        let valid_1 = Circle::new(Point3d::ORIGIN, 100f32);
+        let valid_1 = Circle::new(Point3d::ORIGIN, 100f64).unwrap();
         assert!(valid_1.is_valid());
-        
-        let tiny = Circle::new(Point3d::ORIGIN, 0.000001f32);
+
+        let tiny = Circle::new(Point3d::ORIGIN, 0.000001f64).unwrap();
         assert!(tiny.is_valid());
+        assert!(!tiny.is_valid_within(&Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn transform_similarity()
+    {
+        let mut circle = Circle::new(Point3d::ORIGIN, 10f64).unwrap();
+        circle.scale(&Point3d::new(10f64, 0f64, 0f64), 2f64).unwrap();
+
+        assert_eq!(20f64, circle.radius);
+        assert_eq!(-10f64, circle.center().x);
+    }
+
+    #[test]
+    pub fn transform_non_uniform_fails()
+    {
+        let mut circle = Circle::new(Point3d::ORIGIN, 10f64).unwrap();
+        let stretch = Transform::scale_non_uniform(&Plane::WORLDXY, 2f64, 1f64, 1f64);
+
+        assert_eq!(Err(TransformError::ShapeNotPreserved), circle.transform(&stretch));
+        assert_eq!(10f64, circle.radius);
+    }
+
+    #[test]
+    pub fn transform_stretch_along_normal()
+    {
+        let mut circle = Circle::new(Point3d::ORIGIN, 10f64).unwrap();
+        let stretch = Transform::scale_non_uniform(&Plane::WORLDXY, 1f64, 1f64, 3f64);
+
+        assert!(circle.transform(&stretch).is_ok());
+        assert_eq!(10f64, circle.radius);
     }
-    
+
+    #[test]
+    pub fn evaluation()
+    {
+        let circle = Circle::from_center_normal_radius(Point3d::new(1f64, 2f64, 3f64), Vector3d::ZAXIS, 2f64).unwrap();
+
+        let start = circle.point_at(0f64);
+        let quarter = circle.point_at(FRAC_PI_2);
+        assert!(TOLERANCE.equals(2f64, start.distance_to(&circle.center())));
+        assert!(TOLERANCE.equals(2f64, quarter.distance_to(&circle.center())));
+        assert!((quarter - circle.center()).epsilon_equals(&(circle.plane.y * 2f64), &TOLERANCE));
+
+        assert!(circle.tangent_at(0f64).epsilon_equals(&circle.plane.y, &TOLERANCE));
+        assert!(TOLERANCE.equals(4f64 * PI, circle.circumference()));
+        assert!(TOLERANCE.equals(4f64 * PI, circle.area()));
+    }
+
+    #[test]
+    pub fn closest_point()
+    {
+        let circle = Circle::new(Point3d::ORIGIN, 5f64).unwrap();
+
+        let closest = circle.closest_point(&Point3d::new(0f64, -10f64, 4f64)).unwrap();
+        assert!(closest.epsilon_equals(&Point3d::new(0f64, -5f64, 0f64), &TOLERANCE));
+        assert!(TOLERANCE.angle_equals(3f64 * FRAC_PI_2, circle.closest_parameter(&closest).unwrap()));
+        assert!(circle.closest_point(&Point3d::new(0f64, 0f64, 7f64)).is_none());
+    }
+
+    #[test]
+    pub fn from_three_points()
+    {
+        let circle = Circle::from_three_points(Point3d::new(5f64, 0f64, 1f64),
+                                               Point3d::new(0f64, 5f64, 1f64),
+                                               Point3d::new(-5f64, 0f64, 1f64)).unwrap();
+
+        assert!(circle.center().epsilon_equals(&Point3d::new(0f64, 0f64, 1f64), &TOLERANCE));
+        assert!(TOLERANCE.equals(5f64, circle.radius));
+        assert!(circle.normal().epsilon_equals(&Vector3d::ZAXIS, &TOLERANCE));
+        assert!(circle.is_valid());
+
+        let colinear = Circle::from_three_points(Point3d::ORIGIN, Point3d::new(1f64, 0f64, 0f64), Point3d::new(2f64, 0f64, 0f64));
+        assert_eq!(Some(CircleError::Plane(PlaneError::Colinear)), colinear.err());
+    }
+
+    #[test]
+    pub fn bounding_box()
+    {
+        let tilted = Circle::from_center_normal_radius(Point3d::ORIGIN, Vector3d::new(1f64, 0f64, 1f64), 2f64).unwrap();
+        let bounds = tilted.bounding_box();
+        let reach = 2f64.sqrt();
+
+        assert!(bounds.max().epsilon_equals(&Point3d::new(reach, 2f64, reach), &TOLERANCE));
+        assert!(bounds.min().epsilon_equals(&Point3d::new(-reach, -2f64, -reach), &TOLERANCE));
+    }
+
+    #[test]
+    pub fn to_polyline()
+    {
+        let circle = Circle::new(Point3d::ORIGIN, 1f64).unwrap();
+        let square = circle.to_polyline(4).unwrap();
+
+        assert!(TOLERANCE.equals(4f64 * 2f64.sqrt(), square.length()));
+        assert_eq!(Some(CircleError::TooFewSegments), circle.to_polyline(2).err());
+    }
+
 }
@@ -0,0 +1,469 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Arc::Arc;
+use crate::geometry::basics::BoundingBox::BoundingBox;
+use crate::geometry::basics::Circle::Circle;
+use crate::geometry::basics::Interval::Interval;
+use crate::geometry::basics::NurbsCurve::NurbsCurve;
+use crate::geometry::basics::Plane::{Plane, PlaneError};
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
+
+/// An Ellipse lying in a plane, centered on the plane origin.
+/// Points are found from the eccentric angle, in radians, measured from the plane's X axis towards its Y axis
+#[derive(Copy, Clone)]
+pub struct Ellipse
+{
+    /// The plane of the ellipse, its origin is the center
+    pub plane: Plane,
+
+    /// The radius along the plane's X axis
+    pub radius1: f64,
+
+    /// The radius along the plane's Y axis
+    pub radius2: f64,
+
+}
+
+impl Ellipse {
+
+    pub const UNSET:Ellipse = Ellipse { plane:Plane::UNSET, radius1:f64::NAN, radius2:f64::NAN };
+
+    /// Constructs an ellipse on a plane, centered at the plane origin
+    pub fn from_plane(plane:Plane, radius1:f64, radius2:f64) -> Result<Ellipse, EllipseError> {
+        if !(radius1 > 0f64 && radius1.is_finite() && radius2 > 0f64 && radius2.is_finite()) {
+            return Err(EllipseError::InvalidRadius);
+        }
+
+        Ok(Ellipse { plane, radius1, radius2 })
+    }
+
+    /// Returns the ellipse a circle becomes under any affine transform, including non-uniform scaling.
+    /// The angles on the result are measured from its longest or shortest axis, not the circle's X axis
+    pub fn from_transformed_circle(circle:&Circle, xform:&Transform) -> Result<Ellipse, TransformError> {
+        let mut ellipse = Ellipse::from(*circle);
+        ellipse.transform(xform)?;
+        Ok(ellipse)
+    }
+
+    // Methods
+
+    /// Returns the center of the ellipse
+    pub fn center(&self) -> Point3d {
+        self.plane.origin
+    }
+
+    /// Returns the direction the ellipse faces
+    pub fn normal(&self) -> Vector3d {
+        self.plane.z
+    }
+
+    /// Returns the point on the ellipse at the eccentric `angle`
+    pub fn point_at(&self, angle:f64) -> Point3d {
+        let (sin, cos) = angle.sin_cos();
+        self.plane.origin + self.plane.x * (self.radius1 * cos) + self.plane.y * (self.radius2 * sin)
+    }
+
+    /// Returns the unit tangent at `angle`, pointing in the direction of increasing angle
+    pub fn tangent_at(&self, angle:f64) -> Vector3d {
+        let (sin, cos) = angle.sin_cos();
+        Vector3d::unitize(&(self.plane.y * (self.radius2 * cos) - self.plane.x * (self.radius1 * sin)))
+    }
+
+    /// Returns the area enclosed by the ellipse
+    pub fn area(&self) -> f64 {
+        PI * self.radius1 * self.radius2
+    }
+
+    /// Returns how far the ellipse is from round, 0 for a circle and approaching 1 as it flattens
+    pub fn eccentricity(&self) -> f64 {
+        let (major, minor) = self.major_minor();
+        (1f64 - (minor * minor) / (major * major)).sqrt()
+    }
+
+    /// Returns the length around the ellipse.
+    /// The Gauss-Kummer series is summed using the arithmetic-geometric mean, which doubles the digits each step
+    /// and stops once a step no longer changes the result
+    /// https://en.wikipedia.org/wiki/Ellipse#Circumference
+    pub fn perimeter(&self) -> f64 {
+        let (major, minor) = self.major_minor();
+        let (mut a, mut b) = (major, minor);
+        let mut sum = (a * a - b * b) / 2f64;
+        let mut power = 1f64;
+
+        while a - b > f64::EPSILON * a {
+            let c = (a - b) / 2f64;
+            (a, b) = ((a + b) / 2f64, (a * b).sqrt());
+            power *= 2f64;
+            sum += power * c * c / 2f64;
+        }
+
+        2f64 * PI * (major * major - sum) / a
+    }
+
+    /// Returns the two foci, on the longer axis either side of the center.
+    /// The distances from any point on the ellipse to both foci sum to twice the longer radius
+    pub fn foci(&self) -> (Point3d, Point3d) {
+        let (major, minor) = self.major_minor();
+        let axis = if self.radius1 >= self.radius2 { self.plane.x } else { self.plane.y };
+        let offset = axis * (major * major - minor * minor).sqrt();
+
+        (self.center() + offset, self.center() - offset)
+    }
+
+    /// Returns the eccentric angle of the closest point on the ellipse, from 0 to 2 PI.
+    /// The foot of the perpendicular is found by bisection, which always converges
+    /// https://www.geometrictools.com/Documentation/DistancePointEllipseEllipsoid.pdf
+    pub fn closest_parameter(&self, point:&Point3d) -> f64 {
+        let offset = *point - self.plane.origin;
+        let (u, v) = (offset.dot(&self.plane.x), offset.dot(&self.plane.y));
+
+        // Solve in the first quadrant, with the longer axis first, then put the signs and order back
+        let swapped = self.radius2 > self.radius1;
+        let (e0, e1, y0, y1) = if swapped {
+            (self.radius2, self.radius1, v.abs(), u.abs())
+        } else {
+            (self.radius1, self.radius2, u.abs(), v.abs())
+        };
+
+        let (x0, x1) = if y1 > 0f64 {
+            if y0 > 0f64 {
+                let z0 = y0 / e0;
+                let z1 = y1 / e1;
+                let g = z0 * z0 + z1 * z1 - 1f64;
+                if g != 0f64 {
+                    let r0 = (e0 / e1) * (e0 / e1);
+                    let t = Ellipse::robust_root(r0, z0, z1, g);
+                    (r0 * y0 / (t + r0 - 1f64), y1 / t)
+                } else {
+                    (y0, y1)
+                }
+            } else {
+                (0f64, e1)
+            }
+        } else {
+            let numerator = e0 * y0;
+            let denominator = e0 * e0 - e1 * e1;
+            if numerator < denominator {
+                let ratio = numerator / denominator;
+                (e0 * ratio, e1 * (1f64 - ratio * ratio).max(0f64).sqrt())
+            } else {
+                (e0, 0f64)
+            }
+        };
+
+        let (cos, sin) = if swapped { (x1 / e1, x0 / e0) } else { (x0 / e0, x1 / e1) };
+        let angle = (sin * v.signum()).atan2(cos * u.signum());
+        if angle < 0f64 { angle + 2f64 * PI } else { angle }
+    }
+
+    /// Returns the closest point on the ellipse
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        self.point_at(self.closest_parameter(point))
+    }
+
+    /// Returns the smallest world aligned box containing the ellipse
+    pub fn bounding_box(&self) -> BoundingBox {
+        let reach = |x:f64, y:f64| ((self.radius1 * x).powi(2) + (self.radius2 * y).powi(2)).sqrt();
+        let extent = Vector3d::new(reach(self.plane.x.x, self.plane.y.x),
+                                   reach(self.plane.x.y, self.plane.y.y),
+                                   reach(self.plane.x.z, self.plane.y.z));
+
+        BoundingBox::new(self.center() - extent, self.center() + extent)
+    }
+
+    /// Returns the ellipse as an exact rational quadratic NurbsCurve with four spans, over the domain 0 to 2 PI.
+    /// It is the unit circle's curve stretched along each axis, so only the knots fall on the same points as the angles.
+    /// Returns None if the ellipse is not valid
+    pub fn to_nurbs(&self) -> Option<NurbsCurve> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        let unit = Arc { circle: Circle { plane: self.plane, radius: 1f64 }, angle: Interval::new(0f64, 2f64 * PI) };
+        let circle = unit.to_nurbs()?;
+        let points = circle.points().iter().map(|point| {
+            let local = self.plane.remap_to_plane_space(point);
+            self.plane.point_at(local.x * self.radius1, local.y * self.radius2, 0f64)
+        }).collect();
+
+        NurbsCurve::new(2, points, circle.weights().to_vec(), circle.knots().to_vec()).ok()
+    }
+
+    /// Returns the longer and shorter radius
+    fn major_minor(&self) -> (f64, f64) {
+        (self.radius1.max(self.radius2), self.radius1.min(self.radius2))
+    }
+
+    /// Finds the root of (r0 z0 / (t + r0 - 1))² + (z1 / t)² - 1 by bisection.
+    /// Solving for t rather than Eberly's s = t - 1 keeps full precision when the point is almost on the long axis
+    fn robust_root(r0:f64, z0:f64, z1:f64, g:f64) -> f64 {
+        let n0 = r0 * z0;
+        let shift = r0 - 1f64;
+        let mut t0 = z1;
+        let mut t1 = if g < 0f64 { 1f64 } else { n0.hypot(z1) };
+        let mut t = t0;
+
+        // Each step halves the bracket, so the loop ends once the bracket cannot shrink any further
+        for _ in 0..2200 {
+            t = (t0 + t1) / 2f64;
+            if t == t0 || t == t1 {
+                break;
+            }
+
+            let ratio0 = n0 / (t + shift);
+            let ratio1 = z1 / t;
+            let g = ratio0 * ratio0 + ratio1 * ratio1 - 1f64;
+            if g > 0f64 {
+                t0 = t;
+            } else if g < 0f64 {
+                t1 = t;
+            } else {
+                break;
+            }
+        }
+
+        t
+    }
+
+}
+
+/// The reasons an Ellipse cannot be constructed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EllipseError {
+    /// A radius is zero, negative or not finite
+    InvalidRadius,
+    /// No plane could be made for the ellipse
+    Plane(PlaneError),
+}
+
+impl From<PlaneError> for EllipseError {
+    fn from(error: PlaneError) -> Self {
+        EllipseError::Plane(error)
+    }
+}
+
+impl fmt::Display for EllipseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EllipseError::InvalidRadius => write!(f, "both radii must be positive and finite"),
+            EllipseError::Plane(error) => write!(f, "the ellipse has no plane, {}", error),
+        }
+    }
+}
+
+impl std::error::Error for EllipseError {}
+
+impl From<Circle> for Ellipse {
+    fn from(circle: Circle) -> Self {
+        Ellipse { plane: circle.plane, radius1: circle.radius, radius2: circle.radius }
+    }
+}
+
+impl IsValid for Ellipse {
+    fn is_valid(&self) -> bool {
+        self.plane.is_valid() &&
+        self.radius1.is_finite() &&
+        self.radius1 > 0f64 &&
+        self.radius2.is_finite() &&
+        self.radius2 > 0f64
+    }
+
+    /// An ellipse is only valid if both radii are larger than the tolerance
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() &&
+        self.radius1 > tolerance.absolute &&
+        self.radius2 > tolerance.absolute
+    }
+}
+
+impl Transformable for Ellipse {
+    /// Any affine transform maps an ellipse to another ellipse.
+    /// The transformed radii are conjugate, so they are turned until they are perpendicular to find the new axes.
+    /// The angles are then measured from the new X axis, which need not be where the old one went
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        if !xform.is_valid() {
+            return Err(TransformError::Invalid);
+        }
+        if !xform.is_affine() {
+            return Err(TransformError::ShapeNotPreserved);
+        }
+
+        let mut a = self.plane.x * self.radius1;
+        let mut b = self.plane.y * self.radius2;
+        a.transform(xform)?;
+        b.transform(xform)?;
+        if a.cross(&b).length() <= Transform::TOLERANCE * a.length() * b.length() {
+            return Err(TransformError::Degenerate);
+        }
+
+        // https://en.wikipedia.org/wiki/Conjugate_diameters
+        let turn = 0.5f64 * (2f64 * a.dot(&b)).atan2(a.dot(&a) - b.dot(&b));
+        let (sin, cos) = turn.sin_cos();
+        let x = a * cos + b * sin;
+        let y = b * cos - a * sin;
+
+        let mut center = self.plane.origin;
+        center.transform(xform)?;
+        let plane = Plane::new(center, x, y).map_err(|_| TransformError::Degenerate)?;
+
+        self.plane = plane;
+        self.radius1 = x.length();
+        self.radius2 = y.length();
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Ellipse {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.plane.epsilon_equals(&other.plane, tolerance) &&
+        tolerance.equals(self.radius1, other.radius1) &&
+        tolerance.equals(self.radius2, other.radius2)
+    }
+}
+
+impl PartialEq for Ellipse {
+    fn eq(&self, other: &Self) -> bool {
+        self.plane.eq(&other.plane) &&
+        self.radius1.eq(&other.radius1) &&
+        self.radius2.eq(&other.radius2)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
+
+    fn on_ellipse(ellipse:&Ellipse, point:&Point3d) -> bool {
+        let local = ellipse.plane.remap_to_plane_space(point);
+        let (u, v) = (local.x / ellipse.radius1, local.y / ellipse.radius2);
+        TOLERANCE.equals(1f64, u * u + v * v) && TOLERANCE.equals(0f64, local.z)
+    }
+
+    #[test]
+    pub fn is_valid()
+    {
+        assert_eq!(Some(EllipseError::InvalidRadius), Ellipse::from_plane(Plane::WORLDXY, 1f64, 0f64).err());
+        assert!(!Ellipse::UNSET.is_valid());
+
+        let ellipse = Ellipse::from_plane(Plane::WORLDXY, 3f64, 0.0001f64).unwrap();
+        assert!(ellipse.is_valid());
+        assert!(!ellipse.is_valid_within(&TOLERANCE));
+    }
+
+    #[test]
+    pub fn evaluation()
+    {
+        let ellipse = Ellipse::from_plane(Plane::WORLDXY, 3f64, 2f64).unwrap();
+
+        assert!(ellipse.point_at(0f64).epsilon_equals(&Point3d::new(3f64, 0f64, 0f64), &TOLERANCE));
+        assert!(ellipse.point_at(FRAC_PI_2).epsilon_equals(&Point3d::new(0f64, 2f64, 0f64), &TOLERANCE));
+        assert!(ellipse.tangent_at(0f64).epsilon_equals(&Vector3d::YAXIS, &TOLERANCE));
+        assert!(TOLERANCE.equals(6f64 * PI, ellipse.area()));
+        assert!(TOLERANCE.equals(5f64.sqrt() / 3f64, ellipse.eccentricity()));
+    }
+
+    #[test]
+    pub fn perimeter()
+    {
+        let circle = Ellipse::from(Circle::new(Point3d::ORIGIN, 2f64).unwrap());
+        assert!(TOLERANCE.equals(4f64 * PI, circle.perimeter()));
+
+        let ellipse = Ellipse::from_plane(Plane::WORLDXY, 1f64, 2f64).unwrap();
+        assert!((ellipse.perimeter() - 9.688448220547675f64).abs() < 1e-12);
+
+        let flat = Ellipse::from_plane(Plane::WORLDXY, 1f64, 1e-9f64).unwrap();
+        assert!(TOLERANCE.equals(4f64, flat.perimeter()));
+    }
+
+    #[test]
+    pub fn foci()
+    {
+        let ellipse = Ellipse::from_plane(Plane::WORLDXY, 3f64, 5f64).unwrap();
+        let (a, b) = ellipse.foci();
+
+        assert!(a.epsilon_equals(&Point3d::new(0f64, 4f64, 0f64), &TOLERANCE));
+        for i in 0..8 {
+            let point = ellipse.point_at(i as f64);
+            assert!(TOLERANCE.equals(10f64, point.distance_to(&a) + point.distance_to(&b)));
+        }
+    }
+
+    #[test]
+    pub fn closest_point()
+    {
+        let plane = Plane::new(Point3d::new(1f64, 2f64, 3f64), Vector3d::new(1f64, 1f64, 0f64), Vector3d::new(-1f64, 1f64, 1f64)).unwrap();
+        for (radius1, radius2) in [(4f64, 1.5f64), (1.5f64, 4f64), (2f64, 2f64)] {
+            let ellipse = Ellipse::from_plane(plane, radius1, radius2).unwrap();
+
+            for point in [Point3d::new(7f64, -1f64, 2f64), Point3d::new(1.2f64, 2.1f64, 3f64),
+                          Point3d::new(-3f64, 4f64, 0f64), plane.point_at(0.5f64, 0f64, 1f64)] {
+                let closest = ellipse.closest_point(&point);
+                let best = (0..20000).map(|i| ellipse.point_at(2f64 * PI * i as f64 / 20000f64).distance_to(&point))
+                                     .fold(f64::INFINITY, f64::min);
+
+                assert!(on_ellipse(&ellipse, &closest));
+                assert!(closest.distance_to(&point) <= best + 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    pub fn bounding_box()
+    {
+        let plane = Plane::new(Point3d::ORIGIN, Vector3d::new(1f64, 0f64, 1f64), Vector3d::YAXIS).unwrap();
+        let ellipse = Ellipse::from_plane(plane, 2f64, 1f64).unwrap();
+        let reach = 2f64.sqrt();
+
+        assert!(ellipse.bounding_box().max().epsilon_equals(&Point3d::new(reach, 1f64, reach), &TOLERANCE));
+    }
+
+    #[test]
+    pub fn to_nurbs()
+    {
+        let plane = Plane::from_point_normal(Point3d::new(1f64, 2f64, 3f64), Vector3d::new(1f64, 2f64, 2f64)).unwrap();
+        let ellipse = Ellipse::from_plane(plane, 4f64, 1f64).unwrap();
+        let curve = ellipse.to_nurbs().unwrap();
+
+        assert_eq!(9, curve.points().len());
+        assert!(curve.point_at(0f64).epsilon_equals(&ellipse.point_at(0f64), &TOLERANCE));
+        assert!(curve.point_at(PI).epsilon_equals(&ellipse.point_at(PI), &TOLERANCE));
+        for i in 0..=40 {
+            assert!(on_ellipse(&ellipse, &curve.point_at(2f64 * PI * i as f64 / 40f64)));
+        }
+    }
+
+    #[test]
+    pub fn from_transformed_circle()
+    {
+        let circle = Circle::new(Point3d::ORIGIN, 2f64).unwrap();
+        let stretch = Transform::scale_non_uniform(&Plane::WORLDXY, 1f64, 3f64, 1f64);
+        let ellipse = Ellipse::from_transformed_circle(&circle, &stretch).unwrap();
+
+        assert!(TOLERANCE.equals(12f64 * PI, ellipse.area()));
+        assert!(TOLERANCE.equals(6f64, ellipse.radius1.max(ellipse.radius2)));
+
+        let shear = Transform::shear(&Plane::WORLDXY, &Vector3d::new(1f64, 0.5f64, 0f64), &Vector3d::YAXIS, &Vector3d::ZAXIS);
+        let skewed = Ellipse::from_transformed_circle(&circle, &(shear * stretch)).unwrap();
+        for i in 0..12 {
+            let mut point = circle.point_at(i as f64);
+            point.transform(&(shear * stretch)).unwrap();
+            assert!(on_ellipse(&skewed, &point));
+        }
+        assert!(skewed.is_valid());
+
+        let flatten = Transform::scale_non_uniform(&Plane::WORLDXY, 1f64, 0f64, 1f64);
+        assert_eq!(Some(TransformError::Degenerate), Ellipse::from_transformed_circle(&circle, &flatten).err());
+    }
+
+}
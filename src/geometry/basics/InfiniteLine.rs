@@ -0,0 +1,114 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::Transformable::Transformable;
+use crate::geometry::basics::Line::Line;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::geometry::Transform::{Transform, TransformError};
+
+/// A Line passing through an origin that continues forever in both directions
+#[derive(Copy, Clone)]
+pub struct InfiniteLine {
+    /// A point on the line, where the parameter is 0
+    pub origin: Point3d,
+    /// The direction of the line, parameters are measured in multiples of its length
+    pub direction: Vector3d,
+}
+
+impl InfiniteLine {
+
+    /// An Unset Line, with an unset origin and direction
+    pub const UNSET:InfiniteLine = InfiniteLine { origin:Point3d::UNSET, direction:Vector3d::UNSET };
+
+    /// Constructs a new InfiniteLine
+    pub fn new(origin:Point3d, direction:Vector3d) -> InfiniteLine {
+        InfiniteLine { origin, direction }
+    }
+
+    /// Constructs the infinite line through a line segment, parameterised the same way as the segment
+    pub fn from_line(line:&Line) -> InfiniteLine {
+        InfiniteLine::new(line.start, line.direction())
+    }
+
+    /// Returns the point at `t` multiples of the direction from the origin
+    pub fn point_at(&self, t:f64) -> Point3d {
+        self.origin + self.direction * t
+    }
+
+    /// Returns the parameter of the closest point on the line
+    pub fn closest_parameter(&self, point:&Point3d) -> f64 {
+        let length_squared = self.direction.dot(&self.direction);
+        if length_squared == 0f64 {
+            return 0f64;
+        }
+
+        (*point - self.origin).dot(&self.direction) / length_squared
+    }
+
+    /// Returns the closest point on the line to `point`
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        self.point_at(self.closest_parameter(point))
+    }
+
+    /// Returns the shortest distance from `point` to the line
+    pub fn distance_to(&self, point:&Point3d) -> f64 {
+        self.closest_point(point).distance_to(point)
+    }
+
+}
+
+impl IsValid for InfiniteLine {
+    fn is_valid(&self) -> bool {
+        self.origin.is_valid() &&
+        self.direction.is_valid() &&
+        self.direction.length() > 0f64
+    }
+
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() && !self.direction.is_tiny(tolerance)
+    }
+}
+
+impl Transformable for InfiniteLine {
+    /// Transforms the origin and a point along the direction, so perspective transforms are handled too
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        let mut origin = self.origin;
+        let mut ahead = self.origin + self.direction;
+        origin.transform(xform)?;
+        ahead.transform(xform)?;
+
+        self.origin = origin;
+        self.direction = ahead - origin;
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for InfiniteLine {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.origin.epsilon_equals(&other.origin, tolerance) &&
+        self.direction.epsilon_equals(&other.direction, tolerance)
+    }
+}
+
+impl PartialEq for InfiniteLine {
+    fn eq(&self, other: &Self) -> bool {
+        self.origin.eq(&other.origin) &&
+        self.direction.eq(&other.direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn closest_point_either_side() {
+        let line = InfiniteLine::from_line(&Line::new(Point3d::ORIGIN, Point3d::new(2f64, 0f64, 0f64)));
+
+        assert!(line.closest_point(&Point3d::new(-5f64, 3f64, 0f64)) == Point3d::new(-5f64, 0f64, 0f64));
+        assert_eq!(-2.5f64, line.closest_parameter(&Point3d::new(-5f64, 3f64, 0f64)));
+        assert_eq!(3f64, line.distance_to(&Point3d::new(100f64, 3f64, 0f64)));
+    }
+
+}
@@ -1,104 +1,334 @@
-use std::ops::{self, Add};
+use std::ops;
 
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
 
-// Should Intervals be able to have a reverse direction?
-
-/// An interval between two numbers
+/// A directed interval between two numbers, running from `t0` to `t1`.
+/// Like Rhino's domains an interval may be decreasing, so `t0` is not always the smaller end
+#[derive(Copy, Clone)]
 pub struct Interval {
-    /// The minimum value
-    min:f32,
-    /// The maximum value
-    max:f32
+    /// The value the interval starts at
+    t0:f64,
+    /// The value the interval ends at
+    t1:f64
+}
+
+/// Whether the ends of an interval count as inside it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Containment {
+    /// The ends are inside the interval
+    Inclusive,
+    /// Only values strictly between the ends are inside the interval
+    Strict,
 }
 
 impl Interval {
 
     /// An Unset Interval
-    pub const UNSET:Interval = Interval { min:f32::NAN, max:f32::NAN };
+    pub const UNSET:Interval = Interval { t0:f64::NAN, t1:f64::NAN };
 
     /// A Zero Interval
-    pub const ZERO:Interval = Interval { min:0f32, max:0f32 };
+    pub const ZERO:Interval = Interval { t0:0f64, t1:0f64 };
+
+    /// The interval from 0 to 1, the domain normalized parameters are measured in
+    pub const UNIT:Interval = Interval { t0:0f64, t1:1f64 };
+
+    pub fn new(t0:f64, t1:f64) -> Interval {
+        Interval { t0, t1 }
+    }
+
+    /// Returns the value the interval starts at
+    pub fn t0(&self) -> f64 {
+        self.t0
+    }
+
+    /// Returns the value the interval ends at
+    pub fn t1(&self) -> f64 {
+        self.t1
+    }
 
-    pub fn new(min:f32, max:f32) -> Interval {
-        Interval { min, max }
+    /// Returns the smaller end of the interval
+    pub fn min(&self) -> f64 {
+        self.t0.min(self.t1)
     }
 
-    pub fn mid(&self) -> f32 {
-        self.min + ((self.max - self.min) / 2f32)
+    /// Returns the larger end of the interval
+    pub fn max(&self) -> f64 {
+        self.t0.max(self.t1)
     }
 
+    /// Returns the signed distance from the start to the end, negative for a decreasing interval
+    pub fn length(&self) -> f64 {
+        self.t1 - self.t0
+    }
+
+    pub fn mid(&self) -> f64 {
+        self.t0 + ((self.t1 - self.t0) / 2f64)
+    }
+
+    /// Returns true if the interval runs from a smaller to a larger value
     pub fn is_increasing(&self) -> bool {
-        self.min > self.max
+        self.t0 < self.t1
     }
 
+    /// Returns true if the interval runs from a larger to a smaller value
     pub fn is_decreasing(&self) -> bool {
-        self.min < self.max
+        self.t0 > self.t1
     }
 
-    pub fn from_intersection(i1:Interval, i2:Interval) -> Interval {
-        let mut lower = i1.min;
-        let mut higher = i1.max;
+    /// Returns true if both ends are the same value
+    pub fn is_singleton(&self) -> bool {
+        self.t0 == self.t1
+    }
 
+    /// Returns the overlap of the two intervals, see `intersection`
+    pub fn from_intersection(i1:Interval, i2:Interval) -> Option<Interval> {
+        i1.intersection(&i2)
+    }
 
+    /// Returns the increasing interval spanning both intervals, including any gap between them
+    pub fn from_union(i1:Interval, i2:Interval) -> Interval {
+        Interval::new(i1.min().min(i2.min()), i1.max().max(i2.max()))
+    }
 
-        panic!("Not implemented yet!")
+    /// Returns the increasing interval both intervals cover, or None if they do not overlap.
+    /// Intervals touching at one end give a singleton
+    pub fn intersection(&self, other:&Interval) -> Option<Interval> {
+        let lower = self.min().max(other.min());
+        let higher = self.max().min(other.max());
+        if lower > higher || lower.is_nan() || higher.is_nan() {
+            return None;
+        }
+
+        Some(Interval::new(lower, higher))
     }
 
-    pub fn from_union(i1:Interval, i2:Interval) -> Interval {
-        let mut lower = i1.min;
-        let mut higher = i1.max;
+    /// Returns the increasing interval covering both intervals, or None if there is a gap between them
+    pub fn union(&self, other:&Interval) -> Option<Interval> {
+        self.intersection(other)?;
+        Some(Interval::from_union(*self, *other))
+    }
+
+    /// Returns true if the interval lies inside this one, allowing for tolerance at either end
+    pub fn includes(&self, i1:Interval, tolerance:&Tolerance) -> bool {
+        self.includes_parameter(i1.t0, tolerance) &&
+        self.includes_parameter(i1.t1, tolerance)
+    }
 
-        if i1.min > i2.min {
-            lower = i2.min;
+    /// Returns true if the parameter lies inside the interval, allowing for tolerance at either end
+    pub fn includes_parameter(&self, p:f64, tolerance:&Tolerance) -> bool {
+        let (min, max) = (self.min(), self.max());
+        p >= min - tolerance.allowance(min) &&
+        p <= max + tolerance.allowance(max)
+    }
+
+    /// Returns true if the value lies between the ends, whichever way the interval runs
+    pub fn contains(&self, t:f64, containment:Containment) -> bool {
+        match containment {
+            Containment::Inclusive => t >= self.min() && t <= self.max(),
+            Containment::Strict => t > self.min() && t < self.max(),
         }
+    }
+
+    /// Returns where the value lies along the interval, 0 at `t0` and 1 at `t1`.
+    /// Values outside the interval give numbers outside 0 to 1
+    pub fn normalized_parameter_at(&self, t:f64) -> f64 {
+        (t - self.t0) / (self.t1 - self.t0)
+    }
+
+    /// Returns the value at the normalized parameter, `t0` at 0 and `t1` at 1
+    pub fn parameter_at(&self, normalized:f64) -> f64 {
+        self.t0 + (self.t1 - self.t0) * normalized
+    }
 
-        if i1.max > i2.max {
-            higher = i2.max
+    /// Swaps the ends of a decreasing interval so it increases
+    pub fn make_increasing(&mut self) {
+        if self.is_decreasing() {
+            *self = self.swap();
         }
+    }
 
-        Interval::new(lower, higher)
+    /// Grows the interval to include the value, keeping its direction.
+    /// An unset interval becomes a singleton at the value
+    pub fn grow(&mut self, t:f64) {
+        // f64::min and f64::max ignore NaN, so an unset interval takes the value
+        let (lower, higher) = (self.min().min(t), self.max().max(t));
+        *self = if self.is_decreasing() { Interval::new(higher, lower) } else { Interval::new(lower, higher) };
     }
 
-    pub fn includes(&self, i1:Interval) -> bool {
-        i1.min > self.min &&
-        i1.max < self.max
+    /// Splits the interval in two at `t`, both parts running the same way as this one.
+    /// Returns None unless `t` lies strictly inside
+    pub fn split(&self, t:f64) -> Option<(Interval, Interval)> {
+        if !self.contains(t, Containment::Strict) {
+            return None;
+        }
+
+        Some((Interval::new(self.t0, t), Interval::new(t, self.t1)))
     }
 
-    pub fn includes_parameter(&self, p:f32) -> bool {
-        p > self.min &&
-        p < self.max
+    /// Divides the interval into `count` equal parts, in order from `t0` to `t1`
+    pub fn divide(&self, count:usize) -> Vec<Interval> {
+        (0..count).map(|i| {
+            let start = self.parameter_at(i as f64 / count as f64);
+            let end = if i + 1 == count { self.t1 } else { self.parameter_at((i + 1) as f64 / count as f64) };
+            Interval::new(start, end)
+        }).collect()
     }
 
     pub fn swap(self) -> Interval {
-        Interval::new(self.max, self.min)
+        Interval::new(self.t1, self.t0)
     }
 
 
 }
 
 
-impl ops::Add<f32> for Interval {
+impl ops::Add<f64> for Interval {
     type Output = Interval;
-    fn add(self, shift: f32) -> Self::Output {
-        Interval::new(self.min + shift,
-                        self.max + shift)
+    fn add(self, shift: f64) -> Self::Output {
+        Interval::new(self.t0 + shift,
+                        self.t1 + shift)
     }
 }
 
-impl ops::Sub<f32> for Interval {
+impl ops::Sub<f64> for Interval {
     type Output = Interval;
-    fn sub(self, shift: f32) -> Self::Output {
-        Interval::new(self.min - shift,
-                        self.max - shift)
+    fn sub(self, shift: f64) -> Self::Output {
+        Interval::new(self.t0 - shift,
+                        self.t1 - shift)
     }
 }
 
+impl ops::Mul<f64> for Interval {
+    type Output = Interval;
+    fn mul(self, factor: f64) -> Self::Output {
+        Interval::new(self.t0 * factor,
+                        self.t1 * factor)
+    }
+}
 
 
+
+impl EpsilonEquals for Interval {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.t0, other.t0) &&
+        tolerance.equals(self.t1, other.t1)
+    }
+}
+
 impl IsValid for Interval {
     fn is_valid(&self) -> bool {
-        self.max.is_finite() &&
-        self.min.is_finite()
+        self.t1.is_finite() &&
+        self.t0.is_finite()
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.t0.eq(&other.t0) &&
+        self.t1.eq(&other.t1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn includes_parameter_at_ends() {
+        let interval = Interval::new(0f64, 1f64);
+
+        assert!(interval.includes_parameter(0.1f64 * 3f64 + 0.7f64, &Tolerance::DEFAULT));
+        assert!(interval.includes_parameter(-0.0001f64, &Tolerance::DEFAULT));
+        assert!(!interval.includes_parameter(-0.0001f64, &Tolerance::EXACT));
+        assert!(interval.includes(Interval::new(0f64, 1f64), &Tolerance::EXACT));
+        assert!(interval.swap().includes_parameter(0.5f64, &Tolerance::EXACT));
+    }
+
+    #[test]
+    pub fn direction() {
+        let decreasing = Interval::new(3f64, 1f64);
+
+        assert!(decreasing.is_decreasing());
+        assert!(!decreasing.is_increasing());
+        assert!(decreasing.swap().is_increasing());
+        assert!(Interval::new(2f64, 2f64).is_singleton());
+        assert_eq!(-2f64, decreasing.length());
+        assert_eq!(1f64, decreasing.min());
+        assert_eq!(3f64, decreasing.max());
+
+        let mut increasing = decreasing;
+        increasing.make_increasing();
+        assert!(increasing == Interval::new(1f64, 3f64));
+    }
+
+    #[test]
+    pub fn normalized_parameters() {
+        let interval = Interval::new(2f64, -2f64);
+
+        assert_eq!(0.25f64, interval.normalized_parameter_at(1f64));
+        assert_eq!(1f64, interval.parameter_at(0.25f64));
+        assert_eq!(2f64, interval.parameter_at(0f64));
+        assert_eq!(-2f64, interval.parameter_at(1f64));
+    }
+
+    #[test]
+    pub fn union_and_intersection() {
+        let a = Interval::new(0f64, 2f64);
+        let b = Interval::new(3f64, 1f64);
+        let far = Interval::new(5f64, 6f64);
+
+        assert!(a.intersection(&b) == Some(Interval::new(1f64, 2f64)));
+        assert!(a.union(&b) == Some(Interval::new(0f64, 3f64)));
+        assert!(a.intersection(&far).is_none());
+        assert!(a.union(&far).is_none());
+        assert!(Interval::from_union(a, far) == Interval::new(0f64, 6f64));
+        assert!(a.intersection(&Interval::new(2f64, 4f64)) == Some(Interval::new(2f64, 2f64)));
+        assert!(Interval::from_intersection(a, b) == a.intersection(&b));
+    }
+
+    #[test]
+    pub fn contains() {
+        let interval = Interval::new(1f64, 0f64);
+
+        assert!(interval.contains(0f64, Containment::Inclusive));
+        assert!(!interval.contains(0f64, Containment::Strict));
+        assert!(interval.contains(0.5f64, Containment::Strict));
+        assert!(!interval.contains(1.5f64, Containment::Inclusive));
+    }
+
+    #[test]
+    pub fn grow() {
+        let mut decreasing = Interval::new(2f64, 1f64);
+        decreasing.grow(5f64);
+        assert!(decreasing == Interval::new(5f64, 1f64));
+
+        let mut unset = Interval::UNSET;
+        unset.grow(3f64);
+        assert!(unset == Interval::new(3f64, 3f64));
+    }
+
+    #[test]
+    pub fn split_and_divide() {
+        let interval = Interval::new(0f64, 3f64);
+
+        let (low, high) = interval.split(1f64).unwrap();
+        assert!(low == Interval::new(0f64, 1f64));
+        assert!(high == Interval::new(1f64, 3f64));
+        assert!(interval.split(3f64).is_none());
+
+        let parts = Interval::new(3f64, 0f64).divide(3);
+        assert_eq!(3, parts.len());
+        assert!(parts[0] == Interval::new(3f64, 2f64));
+        assert!(parts[2] == Interval::new(1f64, 0f64));
+        assert!(interval.divide(0).is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn scale() {
+        assert!(Interval::new(1f64, 2f64) * 3f64 == Interval::new(3f64, 6f64));
+        assert!(Interval::new(1f64, 2f64) + 1f64 == Interval::new(2f64, 3f64));
+    }
+
+}
@@ -0,0 +1,102 @@
+use std::ops;
+
+use crate::IsValid::IsValid;
+use crate::geometry::basics::IntervalScalar::IntervalScalar;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+
+/// A point or vector whose coordinates are IntervalScalars, so calculations on it carry a certain bound on their rounding error
+#[derive(Copy, Clone)]
+pub struct IntervalPoint3d {
+    pub x:IntervalScalar,
+    pub y:IntervalScalar,
+    pub z:IntervalScalar,
+}
+
+impl IntervalPoint3d {
+
+    pub const UNSET:IntervalPoint3d = IntervalPoint3d { x:IntervalScalar::UNSET, y:IntervalScalar::UNSET, z:IntervalScalar::UNSET };
+
+    pub fn new(x:IntervalScalar, y:IntervalScalar, z:IntervalScalar) -> IntervalPoint3d {
+        IntervalPoint3d { x, y, z }
+    }
+
+    /// Returns the dot product, treating both as vectors
+    pub fn dot(&self, other:&IntervalPoint3d) -> IntervalScalar {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Returns the cross product, treating both as vectors
+    pub fn cross(&self, other:&IntervalPoint3d) -> IntervalPoint3d {
+        IntervalPoint3d::new(self.y * other.z - self.z * other.y,
+                             self.z * other.x - self.x * other.z,
+                             self.x * other.y - self.y * other.x)
+    }
+
+    /// Returns true if every coordinate of the point could be the matching coordinate
+    pub fn contains(&self, point:&Point3d) -> bool {
+        self.x.contains(point.x) &&
+        self.y.contains(point.y) &&
+        self.z.contains(point.z)
+    }
+
+}
+
+impl From<Point3d> for IntervalPoint3d {
+    fn from(point: Point3d) -> Self {
+        IntervalPoint3d::new(point.x.into(), point.y.into(), point.z.into())
+    }
+}
+
+impl From<Vector3d> for IntervalPoint3d {
+    fn from(vector: Vector3d) -> Self {
+        IntervalPoint3d::new(vector.x.into(), vector.y.into(), vector.z.into())
+    }
+}
+
+impl ops::Add<IntervalPoint3d> for IntervalPoint3d {
+    type Output = IntervalPoint3d;
+    fn add(self, other: IntervalPoint3d) -> Self::Output {
+        IntervalPoint3d::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl ops::Sub<IntervalPoint3d> for IntervalPoint3d {
+    type Output = IntervalPoint3d;
+    fn sub(self, other: IntervalPoint3d) -> Self::Output {
+        IntervalPoint3d::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl ops::Mul<IntervalScalar> for IntervalPoint3d {
+    type Output = IntervalPoint3d;
+    fn mul(self, factor: IntervalScalar) -> Self::Output {
+        IntervalPoint3d::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl IsValid for IntervalPoint3d {
+    fn is_valid(&self) -> bool {
+        self.x.is_valid() &&
+        self.y.is_valid() &&
+        self.z.is_valid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn products_contain_float_result() {
+        let a = Vector3d::new(0.1f64, 0.2f64, 0.3f64);
+        let b = Vector3d::new(-0.7f64, 1.3f64, 0.9f64);
+        let (ia, ib) = (IntervalPoint3d::from(a), IntervalPoint3d::from(b));
+
+        assert!(ia.dot(&ib).contains(a.dot(&b)));
+        let cross = a.cross(&b);
+        assert!(ia.cross(&ib).contains(&Point3d::new(cross.x, cross.y, cross.z)));
+        assert!(!IntervalPoint3d::UNSET.is_valid());
+    }
+
+}
@@ -0,0 +1,294 @@
+use std::cmp::Ordering;
+use std::ops;
+
+use crate::IsValid::IsValid;
+use crate::geometry::basics::Interval::Interval;
+
+/// A number known only to lie within an increasing Interval, for evaluating expressions with a guaranteed bound on rounding error.
+/// Every operation rounds its lower bound down and its upper bound up, so the true result of the same expression on
+/// the exact inputs always lies inside. Results that the floating point operation gave exactly are not widened
+/// https://en.wikipedia.org/wiki/Interval_arithmetic
+#[derive(Copy, Clone)]
+pub struct IntervalScalar {
+    interval:Interval,
+}
+
+impl IntervalScalar {
+
+    /// An unset value, the result of operations with no answer such as the square root of a negative number
+    pub const UNSET:IntervalScalar = IntervalScalar { interval:Interval::UNSET };
+
+    /// Exactly zero
+    pub const ZERO:IntervalScalar = IntervalScalar { interval:Interval::ZERO };
+
+    /// Creates a value lying somewhere between the two bounds, in either order
+    pub fn new(lower:f64, upper:f64) -> IntervalScalar {
+        let mut interval = Interval::new(lower, upper);
+        interval.make_increasing();
+        IntervalScalar { interval }
+    }
+
+    /// Creates a value known exactly
+    pub fn exact(value:f64) -> IntervalScalar {
+        IntervalScalar { interval:Interval::new(value, value) }
+    }
+
+    /// Returns the interval the value lies in
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// Returns the lower bound
+    pub fn lower(&self) -> f64 {
+        self.interval.t0()
+    }
+
+    /// Returns the upper bound
+    pub fn upper(&self) -> f64 {
+        self.interval.t1()
+    }
+
+    /// Returns the distance between the bounds, which is how uncertain the value is
+    pub fn width(&self) -> f64 {
+        self.interval.length()
+    }
+
+    /// Returns true if the value could be `value`
+    pub fn contains(&self, value:f64) -> bool {
+        value >= self.lower() && value <= self.upper()
+    }
+
+    /// Compares the two values, returning None when their intervals overlap and the order cannot be certain
+    pub fn compare(&self, other:&IntervalScalar) -> Option<Ordering> {
+        if self.upper() < other.lower() {
+            Some(Ordering::Less)
+        } else if self.lower() > other.upper() {
+            Some(Ordering::Greater)
+        } else if self.lower() == self.upper() && other.lower() == other.upper() && self.lower() == other.lower() {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the certain sign of the value compared to zero, or None if the value could be on either side
+    pub fn sign(&self) -> Option<Ordering> {
+        self.compare(&IntervalScalar::ZERO)
+    }
+
+    /// Returns the square root, ignoring any part of the interval below zero.
+    /// Returns UNSET if the value is certainly negative
+    pub fn sqrt(&self) -> IntervalScalar {
+        if self.upper() < 0f64 {
+            return IntervalScalar::UNSET;
+        }
+
+        // The remainder x - s² gives the side of the rounded root the true root is on
+        let root = |value:f64| {
+            let s = value.sqrt();
+            (s, (-s).mul_add(s, value))
+        };
+        let (lower, lower_error) = root(self.lower().max(0f64));
+        let (upper, upper_error) = root(self.upper());
+
+        IntervalScalar::rounded(lower, lower_error, upper, upper_error)
+    }
+
+    /// Returns the value multiplied by itself, which unlike `self * self` can never be negative
+    pub fn square(&self) -> IntervalScalar {
+        let magnitude = IntervalScalar::new(self.lower().abs(), self.upper().abs());
+        let lower = if self.contains(0f64) { IntervalScalar::ZERO } else { magnitude * IntervalScalar::exact(magnitude.lower()) };
+        let upper = magnitude * IntervalScalar::exact(magnitude.upper());
+
+        IntervalScalar::new(lower.lower(), upper.upper())
+    }
+
+    /// Builds the interval from the rounded bounds and the sign of the error each one was rounded with
+    fn rounded(lower:f64, lower_error:f64, upper:f64, upper_error:f64) -> IntervalScalar {
+        IntervalScalar::new(IntervalScalar::round_down(lower, lower_error), IntervalScalar::round_up(upper, upper_error))
+    }
+
+    /// Returns the largest number no more than the true value, which is `value` plus `error`
+    fn round_down(value:f64, error:f64) -> f64 {
+        if value == f64::INFINITY {
+            return f64::MAX;
+        }
+        if value.is_infinite() || error >= 0f64 {
+            return value;
+        }
+
+        value.next_down()
+    }
+
+    /// Returns the smallest number no less than the true value, which is `value` plus `error`
+    fn round_up(value:f64, error:f64) -> f64 {
+        if value == f64::NEG_INFINITY {
+            return f64::MIN;
+        }
+        if value.is_infinite() || error <= 0f64 {
+            return value;
+        }
+
+        value.next_up()
+    }
+
+    /// Returns the rounded sum and the exact error made by rounding it
+    /// https://en.wikipedia.org/wiki/2Sum
+    fn two_sum(a:f64, b:f64) -> (f64, f64) {
+        let sum = a + b;
+        let b_virtual = sum - a;
+        let a_virtual = sum - b_virtual;
+        (sum, (a - a_virtual) + (b - b_virtual))
+    }
+
+    /// Returns the rounded product and the exact error made by rounding it
+    fn two_product(a:f64, b:f64) -> (f64, f64) {
+        let product = a * b;
+        (product, a.mul_add(b, -product))
+    }
+
+}
+
+impl From<f64> for IntervalScalar {
+    fn from(value: f64) -> Self {
+        IntervalScalar::exact(value)
+    }
+}
+
+impl ops::Add<IntervalScalar> for IntervalScalar {
+    type Output = IntervalScalar;
+    fn add(self, other: IntervalScalar) -> Self::Output {
+        let (lower, lower_error) = IntervalScalar::two_sum(self.lower(), other.lower());
+        let (upper, upper_error) = IntervalScalar::two_sum(self.upper(), other.upper());
+        IntervalScalar::rounded(lower, lower_error, upper, upper_error)
+    }
+}
+
+impl ops::Sub<IntervalScalar> for IntervalScalar {
+    type Output = IntervalScalar;
+    fn sub(self, other: IntervalScalar) -> Self::Output {
+        self + (-other)
+    }
+}
+
+impl ops::Neg for IntervalScalar {
+    type Output = IntervalScalar;
+    fn neg(self) -> Self::Output {
+        IntervalScalar::new(-self.upper(), -self.lower())
+    }
+}
+
+impl ops::Mul<IntervalScalar> for IntervalScalar {
+    type Output = IntervalScalar;
+    fn mul(self, other: IntervalScalar) -> Self::Output {
+        // The extremes of the product are always at a pair of bounds
+        let products = [
+            IntervalScalar::two_product(self.lower(), other.lower()),
+            IntervalScalar::two_product(self.lower(), other.upper()),
+            IntervalScalar::two_product(self.upper(), other.lower()),
+            IntervalScalar::two_product(self.upper(), other.upper()),
+        ];
+
+        let lower = products.iter().map(|(p, e)| IntervalScalar::round_down(*p, *e)).fold(f64::INFINITY, f64::min);
+        let upper = products.iter().map(|(p, e)| IntervalScalar::round_up(*p, *e)).fold(f64::NEG_INFINITY, f64::max);
+        IntervalScalar::new(lower, upper)
+    }
+}
+
+impl ops::Div<IntervalScalar> for IntervalScalar {
+    type Output = IntervalScalar;
+    /// Dividing by a value that could be zero could give any number, so the result is unbounded
+    fn div(self, other: IntervalScalar) -> Self::Output {
+        if other.contains(0f64) {
+            return IntervalScalar::new(f64::NEG_INFINITY, f64::INFINITY);
+        }
+
+        // The remainder a - q b has the sign of the error when b is positive, and the opposite when it is negative
+        let quotient = |a:f64, b:f64| {
+            let q = a / b;
+            (q, (-q).mul_add(b, a) * b.signum())
+        };
+        let quotients = [
+            quotient(self.lower(), other.lower()),
+            quotient(self.lower(), other.upper()),
+            quotient(self.upper(), other.lower()),
+            quotient(self.upper(), other.upper()),
+        ];
+
+        let lower = quotients.iter().map(|(q, e)| IntervalScalar::round_down(*q, *e)).fold(f64::INFINITY, f64::min);
+        let upper = quotients.iter().map(|(q, e)| IntervalScalar::round_up(*q, *e)).fold(f64::NEG_INFINITY, f64::max);
+        IntervalScalar::new(lower, upper)
+    }
+}
+
+impl IsValid for IntervalScalar {
+    fn is_valid(&self) -> bool {
+        !self.interval.t0().is_nan() &&
+        !self.interval.t1().is_nan()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn addition_is_certified() {
+        let sum = IntervalScalar::exact(0.1f64) + IntervalScalar::exact(0.2f64);
+
+        assert!(sum.contains(0.1f64 + 0.2f64));
+        assert!(sum.width() > 0f64);
+        assert!(!sum.contains(0.3f64 + 1e-15f64));
+
+        let exact = IntervalScalar::exact(0.5f64) + IntervalScalar::exact(0.25f64);
+        assert_eq!(0f64, exact.width());
+        assert_eq!(0.75f64, exact.lower());
+    }
+
+    #[test]
+    pub fn cancellation_sign() {
+        // (1 + 1e-17) - 1 is zero in floating point, the interval knows it might not be
+        let a = IntervalScalar::exact(1f64) + IntervalScalar::exact(1e-17f64);
+        let difference = a - IntervalScalar::exact(1f64);
+
+        assert!(difference.contains(1e-17f64));
+        assert_eq!(None, difference.sign());
+        assert_eq!(Some(Ordering::Equal), (IntervalScalar::exact(3f64) - IntervalScalar::exact(3f64)).sign());
+        assert_eq!(Some(Ordering::Less), IntervalScalar::new(-2f64, -1f64).sign());
+    }
+
+    #[test]
+    pub fn multiplication_and_division() {
+        let a = IntervalScalar::new(-1f64, 2f64);
+        let b = IntervalScalar::new(3f64, 4f64);
+
+        let product = a * b;
+        assert_eq!(-4f64, product.lower());
+        assert_eq!(8f64, product.upper());
+
+        let third = IntervalScalar::exact(1f64) / IntervalScalar::exact(3f64);
+        assert!(third.lower() < third.upper());
+        assert!(third.contains(1f64 / 3f64));
+        assert_eq!(0f64, (b / IntervalScalar::exact(2f64)).width() - 0.5f64);
+
+        let unbounded = b / a;
+        assert_eq!(f64::NEG_INFINITY, unbounded.lower());
+        assert_eq!(f64::INFINITY, unbounded.upper());
+    }
+
+    #[test]
+    pub fn sqrt_and_square() {
+        let two = IntervalScalar::exact(2f64).sqrt();
+        assert!(two.contains(2f64.sqrt()));
+        assert!(two.width() > 0f64);
+        assert_eq!(0f64, IntervalScalar::exact(4f64).sqrt().width());
+
+        assert!(!IntervalScalar::exact(-1f64).sqrt().is_valid());
+        assert_eq!(0f64, IntervalScalar::new(-1f64, 4f64).sqrt().lower());
+
+        let squared = IntervalScalar::new(-2f64, 1f64).square();
+        assert_eq!(0f64, squared.lower());
+        assert_eq!(4f64, squared.upper());
+    }
+
+}
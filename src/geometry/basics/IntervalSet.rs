@@ -0,0 +1,236 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Interval::{Containment, Interval};
+
+/// A collection of disjoint, increasing intervals kept in order, such as the parts of a curve's domain inside a region.
+/// Intervals that overlap or touch are merged, and intervals with no length are ignored
+#[derive(Clone)]
+pub struct IntervalSet {
+    intervals:Vec<Interval>,
+}
+
+impl IntervalSet {
+
+    /// A set without any intervals
+    pub const EMPTY:IntervalSet = IntervalSet { intervals:Vec::new() };
+
+    /// Creates a set from the intervals, merging any that overlap
+    pub fn new(intervals:Vec<Interval>) -> IntervalSet {
+        intervals.into_iter().collect()
+    }
+
+    /// Returns the intervals in increasing order
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Iterates over the intervals in increasing order
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval> {
+        self.intervals.iter()
+    }
+
+    /// Returns the number of disjoint intervals
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns true if the set holds no intervals
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns the total length of the intervals
+    pub fn length(&self) -> f64 {
+        self.intervals.iter().map(|interval| interval.length()).sum()
+    }
+
+    /// Returns the interval from the start of the first interval to the end of the last, or None if the set is empty
+    pub fn bounds(&self) -> Option<Interval> {
+        let first = self.intervals.first()?;
+        let last = self.intervals.last()?;
+        Some(Interval::new(first.t0(), last.t1()))
+    }
+
+    /// Adds the interval, merging it with any it overlaps or touches.
+    /// Decreasing intervals are made increasing, while unset and zero length intervals are ignored
+    pub fn insert(&mut self, interval:Interval) {
+        let mut merged = interval;
+        merged.make_increasing();
+        if !(merged.is_valid() && merged.is_increasing()) {
+            return;
+        }
+
+        // The intervals are sorted and disjoint, so those touching the new one form a single run
+        let start = self.intervals.partition_point(|existing| existing.t1() < merged.t0());
+        let end = self.intervals.partition_point(|existing| existing.t0() <= merged.t1());
+        for existing in &self.intervals[start..end] {
+            merged = Interval::from_union(merged, *existing);
+        }
+
+        self.intervals.splice(start..end, [merged]);
+    }
+
+    /// Returns true if the value lies in any of the intervals
+    pub fn contains(&self, t:f64, containment:Containment) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.t1() < t);
+        self.intervals.get(index).is_some_and(|interval| interval.contains(t, containment))
+    }
+
+    /// Returns the parts covered by either set
+    pub fn union(&self, other:&IntervalSet) -> IntervalSet {
+        let mut set = self.clone();
+        for interval in other.iter() {
+            set.insert(*interval);
+        }
+
+        set
+    }
+
+    /// Returns the parts covered by both sets
+    pub fn intersection(&self, other:&IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        // Walk both sorted lists together, stepping past whichever interval finishes first
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(overlap) = a.intersection(&b) {
+                if overlap.is_increasing() {
+                    intervals.push(overlap);
+                }
+            }
+
+            if a.t1() < b.t1() { i += 1; } else { j += 1; }
+        }
+
+        IntervalSet { intervals }
+    }
+
+    /// Returns the parts of this set not covered by the other set
+    pub fn difference(&self, other:&IntervalSet) -> IntervalSet {
+        let Some(bounds) = self.bounds() else {
+            return IntervalSet::EMPTY;
+        };
+
+        self.intersection(&other.complement(&bounds))
+    }
+
+    /// Returns the parts of `bounds` not covered by this set
+    pub fn complement(&self, bounds:&Interval) -> IntervalSet {
+        let mut bounds = *bounds;
+        bounds.make_increasing();
+
+        let mut intervals = Vec::new();
+        let mut start = bounds.t0();
+        for interval in self.iter() {
+            let end = interval.t0().min(bounds.t1());
+            if start < end {
+                intervals.push(Interval::new(start, end));
+            }
+            start = start.max(interval.t1());
+        }
+        if start < bounds.t1() {
+            intervals.push(Interval::new(start, bounds.t1()));
+        }
+
+        IntervalSet { intervals }
+    }
+
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I:IntoIterator<Item = Interval>>(intervals:I) -> Self {
+        let mut set = IntervalSet::EMPTY;
+        for interval in intervals {
+            set.insert(interval);
+        }
+
+        set
+    }
+}
+
+impl<'a> IntoIterator for &'a IntervalSet {
+    type Item = &'a Interval;
+    type IntoIter = std::slice::Iter<'a, Interval>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intervals.iter()
+    }
+}
+
+impl EpsilonEquals for IntervalSet {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.intervals.len() == other.intervals.len() &&
+        self.intervals.iter().zip(other.intervals.iter()).all(|(a, b)| a.epsilon_equals(b, tolerance))
+    }
+}
+
+impl PartialEq for IntervalSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.intervals == other.intervals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(pairs:&[(f64, f64)]) -> IntervalSet {
+        pairs.iter().map(|(t0, t1)| Interval::new(*t0, *t1)).collect()
+    }
+
+    #[test]
+    pub fn insert_merges() {
+        let mut intervals = set(&[(5f64, 6f64), (0f64, 1f64), (3f64, 2f64)]);
+        assert!(intervals == set(&[(0f64, 1f64), (2f64, 3f64), (5f64, 6f64)]));
+
+        intervals.insert(Interval::new(0.5f64, 2f64));
+        assert!(intervals == set(&[(0f64, 3f64), (5f64, 6f64)]));
+
+        intervals.insert(Interval::new(4f64, 4f64));
+        intervals.insert(Interval::UNSET);
+        assert_eq!(2, intervals.len());
+
+        intervals.insert(Interval::new(-1f64, 10f64));
+        assert!(intervals == set(&[(-1f64, 10f64)]));
+    }
+
+    #[test]
+    pub fn length_and_contains() {
+        let intervals = set(&[(0f64, 1f64), (2f64, 4f64)]);
+
+        assert_eq!(3f64, intervals.length());
+        assert!(intervals.contains(3f64, Containment::Strict));
+        assert!(intervals.contains(2f64, Containment::Inclusive));
+        assert!(!intervals.contains(2f64, Containment::Strict));
+        assert!(!intervals.contains(1.5f64, Containment::Inclusive));
+        assert!(!intervals.contains(5f64, Containment::Inclusive));
+        assert!(intervals.bounds().unwrap() == Interval::new(0f64, 4f64));
+    }
+
+    #[test]
+    pub fn iterates_in_order() {
+        let intervals = set(&[(7f64, 8f64), (0f64, 1f64), (3f64, 4f64)]);
+        let starts:Vec<f64> = intervals.iter().map(|interval| interval.t0()).collect();
+
+        assert_eq!(vec![0f64, 3f64, 7f64], starts);
+        assert_eq!(3, (&intervals).into_iter().count());
+    }
+
+    #[test]
+    pub fn set_operations() {
+        let a = set(&[(0f64, 4f64), (6f64, 8f64)]);
+        let b = set(&[(1f64, 2f64), (3f64, 7f64)]);
+
+        assert!(a.union(&b) == set(&[(0f64, 8f64)]));
+        assert!(a.intersection(&b) == set(&[(1f64, 2f64), (3f64, 4f64), (6f64, 7f64)]));
+        assert!(a.difference(&b) == set(&[(0f64, 1f64), (2f64, 3f64), (7f64, 8f64)]));
+        assert!(b.difference(&a) == set(&[(4f64, 6f64)]));
+        assert!(a.complement(&Interval::new(-1f64, 10f64)) == set(&[(-1f64, 0f64), (4f64, 6f64), (8f64, 10f64)]));
+        assert!(a.complement(&Interval::new(1f64, 3f64)).is_empty());
+        assert!(IntervalSet::EMPTY.complement(&Interval::new(0f64, 1f64)) == set(&[(0f64, 1f64)]));
+        assert!(a.intersection(&set(&[(4f64, 6f64)])).is_empty());
+    }
+
+}
@@ -1,8 +1,13 @@
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
 use crate::geometry::basics::Point3d::Point3d;
 use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
 
 /// A Line segment constrained between two points
+#[derive(Copy, Clone)]
 pub struct Line {
     /// The start of the line
     pub start: Point3d,
@@ -21,21 +26,85 @@ impl Line {
         }
     }
 
-    pub fn new_given_direction(origin: Point3d, direction: Vector3d, distance:f32)
-    {
+    /// Constructs a line from a start point, a direction and a length.
+    /// The direction does not need to be unitized
+    pub fn from_sdl(start: Point3d, direction: Vector3d, length:f64) -> Line {
         let unit_direction = Vector3d::unitize(&direction);
-        let adjusted_amplitude = Vector3d::multiply_by_factor(&unit_direction, distance);
-
-        let end = Point3d::new(0f32, 0f32, 0f32);
-
-        Line::new(origin, end);
+        Line::new(start, start + unit_direction * length)
     }
 
     /// Returns the Direction of the Line
     pub fn get_start_tangeant(&self) -> Vector3d {
-        Vector3d::new(self.start.x - self.end.x,
-                    self.start.y - self.end.y,
-                    self.start.z - self.end.z)
+        self.direction()
+    }
+
+    /// Returns the vector from the start to the end of the line
+    pub fn direction(&self) -> Vector3d {
+        self.end - self.start
+    }
+
+    /// Returns the unitized direction of the line
+    pub fn unit_tangent(&self) -> Vector3d {
+        Vector3d::unitize(&self.direction())
+    }
+
+    /// Returns the distance between the start and end
+    pub fn length(&self) -> f64 {
+        self.start.distance_to(&self.end)
+    }
+
+    /// Returns the point halfway along the line
+    pub fn midpoint(&self) -> Point3d {
+        self.point_at(0.5f64)
+    }
+
+    /// Returns the point at the normalized parameter `t`, where 0 is the start and 1 is the end.
+    /// Parameters outside 0 to 1 continue along the line beyond its ends
+    pub fn point_at(&self, t:f64) -> Point3d {
+        self.start.interpolate(&self.end, t)
+    }
+
+    /// Returns the normalized parameter of the point on the infinite line closest to `point`.
+    /// A zero length line returns 0
+    pub fn closest_parameter(&self, point:&Point3d) -> f64 {
+        let direction = self.direction();
+        let length_squared = direction.dot(&direction);
+        if length_squared == 0f64 {
+            return 0f64;
+        }
+
+        (*point - self.start).dot(&direction) / length_squared
+    }
+
+    /// Returns the closest point on the line to `point`.
+    /// If `limit_to_segment` is false the line is treated as infinite
+    pub fn closest_point(&self, point:&Point3d, limit_to_segment:bool) -> Point3d {
+        let mut t = self.closest_parameter(point);
+        if limit_to_segment {
+            t = t.clamp(0f64, 1f64);
+        }
+
+        self.point_at(t)
+    }
+
+    /// Returns the shortest distance from `point` to the line segment
+    pub fn distance_to(&self, point:&Point3d) -> f64 {
+        self.closest_point(point, true).distance_to(point)
+    }
+
+    /// Lengthens the line by moving the start back by `start` and the end forward by `end`.
+    /// Negative values shorten the line
+    pub fn extend(&mut self, start:f64, end:f64) {
+        let tangent = self.unit_tangent();
+        let (old_start, old_end) = (self.start, self.end);
+
+        self.start = old_start - tangent * start;
+        self.end = old_end + tangent * end;
+    }
+
+    /// Swaps the start and end of the line
+    pub fn flip(&mut self) {
+        std::mem::swap(&mut self.start, &mut self.end);
     }
 
 }
@@ -44,6 +113,32 @@ impl IsValid for Line {
     fn is_valid(&self) -> bool {
         self.start.is_valid() && self.end.is_valid()
     }
+
+    /// A line is only valid if its ends are further apart than the tolerance
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() && !self.start.epsilon_equals(&self.end, tolerance)
+    }
+}
+
+impl Transformable for Line {
+    /// Transforms both ends of the line
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        let mut start = self.start;
+        let mut end = self.end;
+        start.transform(xform)?;
+        end.transform(xform)?;
+
+        self.start = start;
+        self.end = end;
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Line {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.start.epsilon_equals(&other.start, tolerance) &&
+        self.end.epsilon_equals(&other.end, tolerance)
+    }
 }
 
 impl PartialEq for Line {
@@ -51,19 +146,68 @@ impl PartialEq for Line {
         self.start.eq(&other.start) &&
         self.end.eq(&other.end)
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn diagonal() -> Line {
+        Line::new(Point3d::new(0f64, 0f64, 0f64), Point3d::new(10f64, 10f64, 0f64))
+    }
+
     #[test]
     pub fn is_valid() {
         assert!(!Line::UNSET.is_valid());
     }
 
+    #[test]
+    pub fn is_valid_within() {
+        let short = Line::new(Point3d::ORIGIN, Point3d::new(0.0001f64, 0f64, 0f64));
+        assert!(short.is_valid());
+        assert!(!short.is_valid_within(&Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn from_sdl() {
+        let line = Line::from_sdl(Point3d::new(1f64, 0f64, 0f64), Vector3d::new(0f64, 5f64, 0f64), 3f64);
+        assert!(line.end == Point3d::new(1f64, 3f64, 0f64));
+        assert_eq!(3f64, line.length());
+    }
+
+    #[test]
+    pub fn point_at() {
+        let line = diagonal();
+        assert!(line.point_at(0f64) == line.start);
+        assert!(line.point_at(1f64) == line.end);
+        assert!(line.midpoint() == Point3d::new(5f64, 5f64, 0f64));
+        assert!(line.point_at(2f64) == Point3d::new(20f64, 20f64, 0f64));
+    }
+
+    #[test]
+    pub fn closest_point() {
+        let line = diagonal();
+        let point = Point3d::new(10f64, 0f64, 0f64);
+
+        assert_eq!(0.5f64, line.closest_parameter(&point));
+        assert!(line.closest_point(&point, true) == Point3d::new(5f64, 5f64, 0f64));
+
+        let beyond = Point3d::new(20f64, 20f64, 5f64);
+        assert!(line.closest_point(&beyond, true) == line.end);
+        assert!(line.closest_point(&beyond, false) == Point3d::new(20f64, 20f64, 0f64));
+        assert_eq!(5f64, line.distance_to(&Point3d::new(10f64, 10f64, 5f64)));
+    }
+
+    #[test]
+    pub fn extend_and_flip() {
+        let mut line = Line::new(Point3d::ORIGIN, Point3d::new(10f64, 0f64, 0f64));
+        line.extend(1f64, 2f64);
+        assert!(line.start == Point3d::new(-1f64, 0f64, 0f64));
+        assert!(line.end == Point3d::new(12f64, 0f64, 0f64));
+
+        line.flip();
+        assert!(line.start == Point3d::new(12f64, 0f64, 0f64));
+        assert!(line.unit_tangent() == -Vector3d::XAXIS);
+    }
+
 }
\ No newline at end of file
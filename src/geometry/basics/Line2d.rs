@@ -0,0 +1,133 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Point2d::Point2d;
+use crate::geometry::basics::Vector2d::Vector2d;
+
+/// A Line segment in two-dimensional space, constrained between two points
+#[derive(Copy, Clone)]
+pub struct Line2d {
+    /// The start of the line
+    pub start: Point2d,
+    /// The end of the line
+    pub end: Point2d,
+}
+
+impl Line2d {
+
+    pub const UNSET:Line2d = Line2d { start:Point2d::UNSET, end:Point2d::UNSET };
+
+    pub fn new(start: Point2d, end: Point2d) -> Line2d {
+        Line2d { start, end }
+    }
+
+    /// Returns the vector from the start to the end of the line
+    pub fn direction(&self) -> Vector2d {
+        self.end - self.start
+    }
+
+    /// Returns the unitized direction of the line
+    pub fn unit_tangent(&self) -> Vector2d {
+        Vector2d::unitize(&self.direction())
+    }
+
+    /// Returns the distance between the start and end
+    pub fn length(&self) -> f64 {
+        self.start.distance_to(&self.end)
+    }
+
+    /// Returns the point halfway along the line
+    pub fn midpoint(&self) -> Point2d {
+        self.point_at(0.5f64)
+    }
+
+    /// Returns the point at the normalized parameter `t`, where 0 is the start and 1 is the end.
+    /// Parameters outside 0 to 1 continue along the line beyond its ends
+    pub fn point_at(&self, t:f64) -> Point2d {
+        self.start.interpolate(&self.end, t)
+    }
+
+    /// Returns the normalized parameter of the point on the infinite line closest to `point`.
+    /// A zero length line returns 0
+    pub fn closest_parameter(&self, point:&Point2d) -> f64 {
+        let direction = self.direction();
+        let length_squared = direction.dot(&direction);
+        if length_squared == 0f64 {
+            return 0f64;
+        }
+
+        (*point - self.start).dot(&direction) / length_squared
+    }
+
+    /// Returns the closest point on the line to `point`.
+    /// If `limit_to_segment` is false the line is treated as infinite
+    pub fn closest_point(&self, point:&Point2d, limit_to_segment:bool) -> Point2d {
+        let mut t = self.closest_parameter(point);
+        if limit_to_segment {
+            t = t.clamp(0f64, 1f64);
+        }
+
+        self.point_at(t)
+    }
+
+    /// Returns the shortest distance from `point` to the line segment
+    pub fn distance_to(&self, point:&Point2d) -> f64 {
+        self.closest_point(point, true).distance_to(point)
+    }
+
+    /// Returns twice the signed area of the triangle from the line to the point.
+    /// It is positive when the point is to the left of the line looking from the start to the end
+    pub fn side_of(&self, point:&Point2d) -> f64 {
+        self.direction().cross(&(*point - self.start))
+    }
+
+    /// Swaps the start and end of the line
+    pub fn flip(&mut self) {
+        std::mem::swap(&mut self.start, &mut self.end);
+    }
+
+}
+
+impl IsValid for Line2d {
+    fn is_valid(&self) -> bool {
+        self.start.is_valid() && self.end.is_valid()
+    }
+
+    /// A line is only valid if its ends are further apart than the tolerance
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() && !self.start.epsilon_equals(&self.end, tolerance)
+    }
+}
+
+impl EpsilonEquals for Line2d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.start.epsilon_equals(&other.start, tolerance) &&
+        self.end.epsilon_equals(&other.end, tolerance)
+    }
+}
+
+impl PartialEq for Line2d {
+    fn eq(&self, other: &Self) -> bool {
+        self.start.eq(&other.start) &&
+        self.end.eq(&other.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn closest_point_and_side() {
+        let line = Line2d::new(Point2d::ORIGIN, Point2d::new(4f64, 0f64));
+
+        assert_eq!(4f64, line.length());
+        assert!(line.closest_point(&Point2d::new(6f64, 3f64), true) == line.end);
+        assert!(line.closest_point(&Point2d::new(6f64, 3f64), false) == Point2d::new(6f64, 0f64));
+        assert_eq!(3f64, line.distance_to(&Point2d::new(1f64, -3f64)));
+        assert!(line.side_of(&Point2d::new(1f64, 1f64)) > 0f64);
+        assert!(line.side_of(&Point2d::new(1f64, -1f64)) < 0f64);
+        assert!(!Line2d::new(Point2d::ORIGIN, Point2d::ORIGIN).is_valid_within(&Tolerance::DEFAULT));
+    }
+
+}
@@ -0,0 +1,119 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
+
+/// A polygon mesh of triangles and quads sharing a list of vertices.
+/// Like Rhino, every face holds four vertex indices and a triangle repeats its third index.
+/// Faces are wound anticlockwise when seen from the side they face
+#[derive(Clone)]
+pub struct Mesh {
+    vertices:Vec<Point3d>,
+    faces:Vec<[usize; 4]>,
+}
+
+impl Mesh {
+
+    pub const UNSET:Mesh = Mesh { vertices:Vec::new(), faces:Vec::new() };
+
+    pub fn new(vertices:Vec<Point3d>, faces:Vec<[usize; 4]>) -> Mesh {
+        Mesh { vertices, faces }
+    }
+
+    /// Returns the vertices the faces index into
+    pub fn vertices(&self) -> &[Point3d] {
+        &self.vertices
+    }
+
+    /// Returns the faces, as indices into the vertices
+    pub fn faces(&self) -> &[[usize; 4]] {
+        &self.faces
+    }
+
+    /// Returns true if the face at `index` has three corners
+    pub fn is_triangle(&self, index:usize) -> bool {
+        self.faces[index][2] == self.faces[index][3]
+    }
+
+    /// Returns the unit normal of the face at `index`.
+    /// Quads use the cross product of their diagonals, so a slightly bent quad still gets an average normal
+    pub fn face_normal(&self, index:usize) -> Vector3d {
+        let [a, b, c, d] = self.faces[index].map(|i| self.vertices[i]);
+        let normal = if self.is_triangle(index) {
+            (b - a).cross(&(c - a))
+        } else {
+            (c - a).cross(&(d - b))
+        };
+
+        Vector3d::unitize(&normal)
+    }
+
+    /// Returns the total area of the faces, splitting each quad into two triangles
+    pub fn area(&self) -> f64 {
+        let triangle = |a:Point3d, b:Point3d, c:Point3d| (b - a).cross(&(c - a)).length() / 2f64;
+
+        self.faces.iter().map(|face| {
+            let [a, b, c, d] = face.map(|i| self.vertices[i]);
+            if face[2] == face[3] {
+                triangle(a, b, c)
+            } else {
+                triangle(a, b, c) + triangle(a, c, d)
+            }
+        }).sum()
+    }
+
+}
+
+impl IsValid for Mesh {
+    fn is_valid(&self) -> bool {
+        !self.faces.is_empty() &&
+        self.vertices.iter().all(|vertex| vertex.is_valid()) &&
+        self.faces.iter().all(|face| face.iter().all(|i| *i < self.vertices.len()))
+    }
+}
+
+impl Transformable for Mesh {
+    /// Transforms every vertex of the mesh
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        let mut vertices = self.vertices.clone();
+        for vertex in vertices.iter_mut() {
+            vertex.transform(xform)?;
+        }
+
+        self.vertices = vertices;
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Mesh {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.faces == other.faces &&
+        self.vertices.len() == other.vertices.len() &&
+        self.vertices.iter().zip(other.vertices.iter()).all(|(a, b)| a.epsilon_equals(b, tolerance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn area_and_normals() {
+        let vertices = vec![Point3d::ORIGIN, Point3d::new(2f64, 0f64, 0f64), Point3d::new(2f64, 1f64, 0f64),
+                            Point3d::new(0f64, 1f64, 0f64), Point3d::new(0f64, 0f64, 1f64)];
+        let mesh = Mesh::new(vertices, vec![[0, 1, 2, 3], [0, 4, 1, 1]]);
+
+        assert!(mesh.is_valid());
+        assert!(!mesh.is_triangle(0));
+        assert!(mesh.is_triangle(1));
+        assert!(Vector3d::ZAXIS == mesh.face_normal(0));
+        assert!(Vector3d::YAXIS == mesh.face_normal(1));
+        assert_eq!(3f64, mesh.area());
+
+        assert!(!Mesh::new(vec![Point3d::ORIGIN], vec![[0, 1, 2, 2]]).is_valid());
+    }
+
+}
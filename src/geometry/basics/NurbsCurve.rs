@@ -0,0 +1,164 @@
+use std::fmt;
+
+use crate::IsValid::IsValid;
+use crate::geometry::basics::Interval::Interval;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Point4d::Point4d;
+
+/// A rational B-spline curve, able to represent lines, conics and free-form curves exactly.
+/// The knot vector is the full vector, with `points + degree + 1` values
+/// https://en.wikipedia.org/wiki/Non-uniform_rational_B-spline
+#[derive(Clone)]
+pub struct NurbsCurve {
+    degree:usize,
+    points:Vec<Point3d>,
+    weights:Vec<f64>,
+    knots:Vec<f64>,
+}
+
+impl NurbsCurve {
+
+    /// Constructs a new NurbsCurve, checking the control points, weights and knots agree
+    pub fn new(degree:usize, points:Vec<Point3d>, weights:Vec<f64>, knots:Vec<f64>) -> Result<NurbsCurve, NurbsError> {
+        if degree == 0 || points.len() <= degree {
+            return Err(NurbsError::TooFewPoints);
+        }
+        if weights.len() != points.len() || weights.iter().any(|w| !(*w > 0f64 && w.is_finite())) {
+            return Err(NurbsError::InvalidWeights);
+        }
+        if knots.len() != points.len() + degree + 1 ||
+           knots.iter().any(|knot| !knot.is_finite()) ||
+           knots.windows(2).any(|pair| pair[0] > pair[1]) ||
+           knots[degree] == knots[points.len()] {
+            return Err(NurbsError::InvalidKnots);
+        }
+
+        Ok(NurbsCurve { degree, points, weights, knots })
+    }
+
+    /// Returns the degree of the curve
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the control points
+    pub fn points(&self) -> &[Point3d] {
+        &self.points
+    }
+
+    /// Returns the weight of each control point
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the full knot vector
+    pub fn knots(&self) -> &[f64] {
+        &self.knots
+    }
+
+    /// Returns the control point at `index` in homogeneous form, multiplied by its weight
+    pub fn homogeneous_point(&self, index:usize) -> Point4d {
+        Point4d::from_weighted(&self.points[index], self.weights[index])
+    }
+
+    /// Returns the range of parameters the curve is defined over
+    pub fn domain(&self) -> Interval {
+        Interval::new(self.knots[self.degree], self.knots[self.points.len()])
+    }
+
+    /// Returns true if the weights differ; when they are all the same the curve is a plain B-spline
+    pub fn is_rational(&self) -> bool {
+        self.weights.iter().any(|w| *w != self.weights[0])
+    }
+
+    /// Returns the point at parameter `t` using de Boor's algorithm on the weighted points.
+    /// Parameters outside the domain are clamped to it
+    pub fn point_at(&self, t:f64) -> Point3d {
+        let p = self.degree;
+        let domain = self.domain();
+        let t = t.clamp(domain.min(), domain.max());
+
+        // The last span with any length that starts at or before t
+        let mut span = p;
+        while span + 1 < self.points.len() && self.knots[span + 1] <= t {
+            span += 1;
+        }
+
+        let mut d:Vec<Point4d> = (0..=p).map(|j| self.homogeneous_point(j + span - p)).collect();
+
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let low = self.knots[j + span - p];
+                let high = self.knots[j + 1 + span - r];
+                let alpha = if high == low { 0f64 } else { (t - low) / (high - low) };
+                d[j] = d[j - 1].interpolate(&d[j], alpha);
+            }
+        }
+
+        // Weights are positive, so the divide always succeeds
+        d[p].euclidean().unwrap_or(Point3d::UNSET)
+    }
+
+}
+
+/// The reasons a NurbsCurve cannot be constructed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NurbsError {
+    /// A curve of degree n needs at least n + 1 control points
+    TooFewPoints,
+    /// There must be one positive weight per control point
+    InvalidWeights,
+    /// The knots must be non-decreasing, one more than the points plus the degree, and span a domain
+    InvalidKnots,
+}
+
+impl fmt::Display for NurbsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NurbsError::TooFewPoints => write!(f, "there are too few control points for the degree"),
+            NurbsError::InvalidWeights => write!(f, "the weights must be positive, one per control point"),
+            NurbsError::InvalidKnots => write!(f, "the knot vector does not match the control points"),
+        }
+    }
+}
+
+impl std::error::Error for NurbsError {}
+
+impl IsValid for NurbsCurve {
+    fn is_valid(&self) -> bool {
+        self.points.iter().all(|point| point.is_valid()) &&
+        self.knots.iter().all(|knot| knot.is_finite())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EpsilonEquals::EpsilonEquals;
+    use crate::Tolerance::Tolerance;
+
+    #[test]
+    pub fn validation() {
+        let points = vec![Point3d::ORIGIN, Point3d::new(1f64, 0f64, 0f64)];
+
+        assert!(NurbsCurve::new(1, points.clone(), vec![1f64; 2], vec![0f64, 0f64, 1f64, 1f64]).is_ok());
+        assert_eq!(Some(NurbsError::TooFewPoints), NurbsCurve::new(2, points.clone(), vec![1f64; 2], vec![0f64; 5]).err());
+        assert_eq!(Some(NurbsError::InvalidWeights), NurbsCurve::new(1, points.clone(), vec![1f64, 0f64], vec![0f64, 0f64, 1f64, 1f64]).err());
+        assert_eq!(Some(NurbsError::InvalidKnots), NurbsCurve::new(1, points, vec![1f64; 2], vec![0f64, 1f64, 0f64, 1f64]).err());
+    }
+
+    #[test]
+    pub fn quarter_circle() {
+        let half_root = 0.5f64.sqrt();
+        let curve = NurbsCurve::new(2,
+                                    vec![Point3d::new(1f64, 0f64, 0f64), Point3d::new(1f64, 1f64, 0f64), Point3d::new(0f64, 1f64, 0f64)],
+                                    vec![1f64, half_root, 1f64],
+                                    vec![0f64, 0f64, 0f64, 1f64, 1f64, 1f64]).unwrap();
+
+        assert!(curve.is_rational());
+        assert!(curve.point_at(0f64).epsilon_equals(&Point3d::new(1f64, 0f64, 0f64), &Tolerance::DEFAULT));
+        assert!(curve.point_at(0.5f64).epsilon_equals(&Point3d::new(half_root, half_root, 0f64), &Tolerance::DEFAULT));
+        assert!(curve.point_at(1f64).epsilon_equals(&Point3d::new(0f64, 1f64, 0f64), &Tolerance::DEFAULT));
+    }
+
+}
@@ -1,8 +1,19 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::IntervalPoint3d::IntervalPoint3d;
+use crate::geometry::basics::Point2d::Point2d;
 use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector2d::Vector2d;
 use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
 
 /// Defines the infinite
+#[derive(Copy, Clone)]
 pub struct Plane {
     /// The origin, this can be unset as its technically unecessary
     pub origin: Point3d,
@@ -28,25 +39,307 @@ impl Plane {
     /// The World ZX Plane
     pub const WORLDZX:Plane = Plane { origin:Point3d::ORIGIN, x:Vector3d::YAXIS, y:Vector3d::ZAXIS, z:Vector3d::XAXIS };
 
-    pub fn new(origin:Point3d, x:Vector3d, y:Vector3d) -> Plane {
-        let z = Plane::cross_product(x.clone(), y.clone());
-        Plane { origin, x, y, z }
+    /// The tolerance a frame must meet to be valid. Unitizing and transforming only
+    /// introduce errors around 1e-15, so anything beyond this is a genuinely bad frame
+    const FRAME_TOLERANCE:Tolerance = Tolerance { absolute:1e-9f64, angle:1e-9f64, relative:0f64 };
+
+    /// Constructs a right-handed plane from an origin and two directions.
+    /// The X axis is unitized and Y is made perpendicular to it (Gram-Schmidt), so only
+    /// the side of the X axis that `y` points to matters
+    pub fn new(origin:Point3d, x:Vector3d, y:Vector3d) -> Result<Plane, PlaneError> {
+        let (x_length, y_length) = (x.length(), y.length());
+        if x_length == 0f64 || y_length == 0f64 || !x_length.is_finite() || !y_length.is_finite() {
+            return Err(PlaneError::ZeroLength);
+        }
+        if x.is_parallel_to(&y, &Plane::FRAME_TOLERANCE) {
+            return Err(PlaneError::ParallelAxes);
+        }
+
+        let x = x / x_length;
+        let y = Vector3d::unitize(&y.reject_from(&x));
+        Ok(Plane { origin, x, y, z:x.cross(&y) })
+    }
+
+    /// Constructs a plane through `origin` facing along `normal`.
+    /// The X axis is chosen arbitrarily, perpendicular to the normal
+    pub fn from_point_normal(origin:Point3d, normal:Vector3d) -> Result<Plane, PlaneError> {
+        let length = normal.length();
+        if length == 0f64 || !length.is_finite() {
+            return Err(PlaneError::ZeroLength);
+        }
+
+        let z = normal / length;
+        let x = Vector3d::unitize(&z.perpendicular_to());
+        let y = z.cross(&x);
+        Ok(Plane { origin, x, y, z })
+    }
+
+    /// Constructs a plane with its origin at `a`, its X axis towards `b`, and `c` on the positive Y side
+    pub fn from_three_points(a:Point3d, b:Point3d, c:Point3d) -> Result<Plane, PlaneError> {
+        let x = b - a;
+        let z = x.cross(&(c - a));
+        if z.length() == 0f64 || !z.is_valid() {
+            return Err(PlaneError::Colinear);
+        }
+
+        let x = Vector3d::unitize(&x);
+        let z = Vector3d::unitize(&z);
+        Ok(Plane { origin:a, x, y:z.cross(&x), z })
+    }
+
+    /// Fits a plane through the points by least squares, using principal component analysis.
+    /// The origin is the centroid of the points and the X axis follows their widest spread.
+    /// Returns the plane along with the largest distance of any point from it
+    pub fn fit_to_points(points:&[Point3d]) -> Result<(Plane, f64), PlaneError> {
+        if points.len() < 3 {
+            return Err(PlaneError::TooFewPoints);
+        }
+
+        let weights = vec![1f64; points.len()];
+        let centroid = match Point3d::barycentric(points, &weights) {
+            Some(centroid) => centroid,
+            None => return Err(PlaneError::TooFewPoints),
+        };
+
+        let mut covariance = [[0f64; 3]; 3];
+        for point in points {
+            let offset = *point - centroid;
+            let d = [offset.x, offset.y, offset.z];
+            for row in 0..3 {
+                for column in 0..3 {
+                    covariance[row][column] += d[row] * d[column];
+                }
+            }
+        }
+
+        let (values, vectors) = Plane::symmetric_eigen(covariance);
+        let mut order = [0, 1, 2];
+        order.sort_by(|a, b| values[*b].total_cmp(&values[*a]));
+
+        let (largest, middle) = (values[order[0]], values[order[1]]);
+        if middle <= largest * 1e-12 || !largest.is_finite() {
+            return Err(PlaneError::Colinear);
+        }
+
+        let x = Vector3d::unitize(&vectors[order[0]]);
+        let y = Vector3d::unitize(&vectors[order[1]]);
+        let plane = Plane { origin:centroid, x, y, z:x.cross(&y) };
+
+        let deviation = points.iter()
+            .map(|point| plane.distance_to(point).abs())
+            .fold(0f64, f64::max);
+
+        Ok((plane, deviation))
+    }
+
+    // Methods
+
+    /// Returns the signed distance from the plane to the point, positive on the side the Z axis faces
+    pub fn distance_to(&self, point:&Point3d) -> f64 {
+        (*point - self.origin).dot(&self.z)
+    }
+
+    /// Returns which side of the plane the point is certainly on, Greater being the side the Z axis faces.
+    /// The distance is found with interval arithmetic, and None is returned when rounding error could put it on either side
+    pub fn side_certified(&self, point:&Point3d) -> Option<Ordering> {
+        let offset = IntervalPoint3d::from(*point) - IntervalPoint3d::from(self.origin);
+        offset.dot(&self.z.into()).sign()
+    }
+
+    /// Returns the closest point on the plane
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        *point - self.z * self.distance_to(point)
+    }
+
+    /// Returns the point at (`u`, `v`, `w`) measured along the plane's X, Y and Z axes
+    pub fn point_at(&self, u:f64, v:f64, w:f64) -> Point3d {
+        self.origin + self.x * u + self.y * v + self.z * w
+    }
+
+    /// Returns the coordinates of a world point measured along the plane's X, Y and Z axes.
+    /// This is the inverse of `point_at`
+    pub fn remap_to_plane_space(&self, point:&Point3d) -> Point3d {
+        let offset = *point - self.origin;
+        Point3d::new(offset.dot(&self.x), offset.dot(&self.y), offset.dot(&self.z))
+    }
+
+    /// Returns the 2D coordinates of a point measured along the plane's X and Y axes, discarding its height above the plane.
+    /// Points on the plane survive a round trip through `lift` unchanged, up to rounding
+    pub fn project(&self, point:&Point3d) -> Point2d {
+        let offset = *point - self.origin;
+        Point2d::new(offset.dot(&self.x), offset.dot(&self.y))
+    }
+
+    /// Returns the world point on the plane at the 2D coordinates. This is the inverse of `project`
+    pub fn lift(&self, point:&Point2d) -> Point3d {
+        self.point_at(point.x, point.y, 0f64)
+    }
+
+    /// Returns the components of a vector along the plane's X and Y axes
+    pub fn project_vector(&self, vector:&Vector3d) -> Vector2d {
+        Vector2d::new(vector.dot(&self.x), vector.dot(&self.y))
+    }
+
+    /// Returns the world vector lying in the plane with the 2D components. This is the inverse of `project_vector`
+    pub fn lift_vector(&self, vector:&Vector2d) -> Vector3d {
+        self.x * vector.x + self.y * vector.y
     }
 
-    /// Returns the Z Vector
-    /// https://en.wikipedia.org/wiki/Cross_product
-    fn cross_product(x:Vector3d, y:Vector3d) -> Vector3d {
-        x * y
+    /// Turns the plane over by swapping the X and Y axes and reversing the Z axis
+    pub fn flip(&mut self) {
+        std::mem::swap(&mut self.x, &mut self.y);
+        self.z = -self.z;
+    }
+
+    /// Spins the X and Y axes about the Z axis by `angle` radians, keeping the origin and normal.
+    /// To rotate the whole plane about another axis use `Transformable::rotate`
+    pub fn rotate_in_plane(&mut self, angle:f64) {
+        let (sin, cos) = angle.sin_cos();
+        let (x, y) = (self.x, self.y);
+
+        self.x = x * cos + y * sin;
+        self.y = y * cos - x * sin;
+    }
+
+    /// Returns the coefficients (a, b, c, d) of the plane equation ax + by + cz + d = 0
+    pub fn equation(&self) -> [f64; 4] {
+        let normal = Vector3d::unitize(&self.z);
+        let d = -normal.dot(&Vector3d::from(self.origin));
+        [normal.x, normal.y, normal.z, d]
+    }
+
+    // Helpers
+
+    /// Finds the eigenvalues and eigenvectors of a symmetric 3x3 matrix using Jacobi rotations
+    /// https://en.wikipedia.org/wiki/Jacobi_eigenvalue_algorithm
+    fn symmetric_eigen(mut a:[[f64; 3]; 3]) -> ([f64; 3], [Vector3d; 3]) {
+        let mut v = [[1f64, 0f64, 0f64], [0f64, 1f64, 0f64], [0f64, 0f64, 1f64]];
+
+        for _sweep in 0..50 {
+            let off_diagonal = a[0][1].abs() + a[0][2].abs() + a[1][2].abs();
+            if off_diagonal == 0f64 {
+                break;
+            }
+
+            for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+                if a[p][q] == 0f64 {
+                    continue;
+                }
+
+                let theta = (a[q][q] - a[p][p]) / (2f64 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1f64).sqrt());
+                let c = 1f64 / (t * t + 1f64).sqrt();
+                let s = t * c;
+
+                // A' = J^T A J, and the rotations accumulate into the eigenvectors V' = V J
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                a[p] = [0, 1, 2].map(|k| c * row_p[k] - s * row_q[k]);
+                a[q] = [0, 1, 2].map(|k| s * row_p[k] + c * row_q[k]);
+            }
+        }
+
+        let column = |i:usize| Vector3d::new(v[0][i], v[1][i], v[2][i]);
+        ([a[0][0], a[1][1], a[2][2]], [column(0), column(1), column(2)])
     }
 
 }
 
+/// The reasons a Plane cannot be constructed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PlaneError {
+    /// A direction needed to orient the plane has no length
+    ZeroLength,
+    /// The X and Y directions point along the same line
+    ParallelAxes,
+    /// The points all lie along one line, so any plane through that line would fit
+    Colinear,
+    /// Not enough points were given to define a plane
+    TooFewPoints,
+}
+
+impl fmt::Display for PlaneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlaneError::ZeroLength => write!(f, "a direction has no length"),
+            PlaneError::ParallelAxes => write!(f, "the axes are parallel"),
+            PlaneError::Colinear => write!(f, "the points are colinear"),
+            PlaneError::TooFewPoints => write!(f, "at least three points are needed"),
+        }
+    }
+}
+
+impl std::error::Error for PlaneError {}
+
 impl IsValid for Plane {
+    /// A plane is valid when it is a right-handed frame of unit, perpendicular axes
     fn is_valid(&self) -> bool {
+        self.is_valid_within(&Plane::FRAME_TOLERANCE)
+    }
+
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
         self.origin.is_valid() &&
         self.x.is_valid() &&
         self.y.is_valid() &&
-        self.z.is_valid()
+        self.z.is_valid() &&
+        self.x.is_unit(tolerance) &&
+        self.y.is_unit(tolerance) &&
+        self.z.is_unit(tolerance) &&
+        self.x.is_perpendicular_to(&self.y, tolerance) &&
+        self.x.cross(&self.y).angle_to(&self.z) <= tolerance.angle
+    }
+}
+
+impl Transformable for Plane {
+    /// Transforms the origin and axes of the plane.
+    /// Fails if the axes would no longer be perpendicular, as the result would not be a valid frame.
+    /// The Z axis is rebuilt from X and Y, so a mirrored plane is still right-handed
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        if !xform.is_valid() {
+            return Err(TransformError::Invalid);
+        }
+        if !xform.is_affine() {
+            return Err(TransformError::ShapeNotPreserved);
+        }
+
+        let mut origin = self.origin;
+        origin.transform(xform)?;
+
+        let mut axes = [self.x, self.y, self.z];
+        for axis in axes.iter_mut() {
+            axis.transform(xform)?;
+            let length = axis.length();
+            if length == 0f64 {
+                return Err(TransformError::Degenerate);
+            }
+            *axis = Vector3d::divide_by_factor(axis, length);
+        }
+
+        for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+            if axes[i].dot(&axes[j]).abs() > Transform::TOLERANCE {
+                return Err(TransformError::ShapeNotPreserved);
+            }
+        }
+
+        self.origin = origin;
+        self.x = axes[0];
+        self.y = axes[1];
+        self.z = axes[0].cross(&axes[1]);
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Plane {
+    /// Origins are compared by distance and the axes by the angle between them
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.origin.epsilon_equals(&other.origin, tolerance) &&
+        self.x.angle_to(&other.x) <= tolerance.angle &&
+        self.y.angle_to(&other.y) <= tolerance.angle &&
+        self.z.angle_to(&other.z) <= tolerance.angle
     }
 }
 
@@ -57,15 +350,184 @@ impl PartialEq for Plane {
         self.y.eq(&other.y) &&
         self.z.eq(&other.z)
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
-    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    pub fn new_is_right_handed() {
+        let plane = Plane::new(Point3d::ORIGIN, Vector3d::YAXIS, Vector3d::ZAXIS).unwrap();
+        assert!(plane.z == Vector3d::XAXIS);
+
+        let flipped = Plane::new(Point3d::ORIGIN, Vector3d::YAXIS, Vector3d::XAXIS).unwrap();
+        assert!(flipped.z == -Vector3d::ZAXIS);
+    }
+
+    #[test]
+    pub fn new_orthonormalizes() {
+        let plane = Plane::new(Point3d::ORIGIN, Vector3d::new(2f64, 0f64, 0f64), Vector3d::new(3f64, 4f64, 0f64)).unwrap();
+
+        assert!(plane.x == Vector3d::XAXIS);
+        assert!(plane.y == Vector3d::YAXIS);
+        assert!(plane.z == Vector3d::ZAXIS);
+        assert!(plane.is_valid());
+    }
+
+    #[test]
+    pub fn new_rejects_bad_axes() {
+        let zero = Plane::new(Point3d::ORIGIN, Vector3d::ORIGIN, Vector3d::YAXIS);
+        assert_eq!(Err(PlaneError::ZeroLength), zero.map(|_| ()));
+
+        let parallel = Plane::new(Point3d::ORIGIN, Vector3d::XAXIS, Vector3d::new(-3f64, 0f64, 0f64));
+        assert_eq!(Err(PlaneError::ParallelAxes), parallel.map(|_| ()));
+    }
+
+    #[test]
+    pub fn is_valid() {
+        assert!(Plane::WORLDXY.is_valid());
+        assert!(Plane::WORLDYZ.is_valid());
+        assert!(Plane::WORLDZX.is_valid());
+        assert!(!Plane::UNSET.is_valid());
+
+        let left_handed = Plane { z:-Vector3d::ZAXIS, ..Plane::WORLDXY };
+        assert!(!left_handed.is_valid());
+
+        let skewed = Plane { y:Vector3d::new(1f64, 1f64, 0f64), ..Plane::WORLDXY };
+        assert!(!skewed.is_valid());
+
+        let stretched = Plane { x:Vector3d::new(2f64, 0f64, 0f64), ..Plane::WORLDXY };
+        assert!(!stretched.is_valid());
+    }
+
+    #[test]
+    pub fn mirror_stays_right_handed() {
+        let mut plane = Plane::WORLDXY;
+        plane.mirror(&Plane::from_point_normal(Point3d::ORIGIN, Vector3d::XAXIS).unwrap()).unwrap();
+
+        assert!(plane.x == -Vector3d::XAXIS);
+        assert!(plane.is_valid());
+    }
+
+    #[test]
+    pub fn from_point_normal() {
+        let plane = Plane::from_point_normal(Point3d::new(1f64, 2f64, 3f64), Vector3d::new(0f64, 0f64, 5f64)).unwrap();
+
+        assert!(plane.z == Vector3d::ZAXIS);
+        assert!(plane.x.is_perpendicular_to(&plane.z, &Tolerance::DEFAULT));
+        assert!(plane.x.cross(&plane.y).epsilon_equals(&plane.z, &Tolerance::DEFAULT));
+        assert_eq!(Err(PlaneError::ZeroLength), Plane::from_point_normal(Point3d::ORIGIN, Vector3d::ORIGIN).map(|_| ()));
+    }
 
+    #[test]
+    pub fn from_three_points() {
+        let plane = Plane::from_three_points(Point3d::new(1f64, 1f64, 0f64),
+                                             Point3d::new(5f64, 1f64, 0f64),
+                                             Point3d::new(3f64, 9f64, 0f64)).unwrap();
+        assert!(plane.x == Vector3d::XAXIS);
+        assert!(plane.y == Vector3d::YAXIS);
+        assert!(plane.z == Vector3d::ZAXIS);
+
+        let colinear = Plane::from_three_points(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 1f64), Point3d::new(2f64, 2f64, 2f64));
+        assert_eq!(Err(PlaneError::Colinear), colinear.map(|_| ()));
+    }
+
+    #[test]
+    pub fn closest_point_and_distance() {
+        let plane = Plane::from_point_normal(Point3d::new(0f64, 0f64, 2f64), Vector3d::ZAXIS).unwrap();
+        let point = Point3d::new(3f64, 4f64, -1f64);
+
+        assert_eq!(-3f64, plane.distance_to(&point));
+        assert!(plane.closest_point(&point) == Point3d::new(3f64, 4f64, 2f64));
+        assert_eq!([0f64, 0f64, 1f64, -2f64], plane.equation());
+    }
+
+    #[test]
+    pub fn side_certified() {
+        let plane = Plane::from_point_normal(Point3d::new(0.1f64, 0.2f64, 0.3f64), Vector3d::new(1f64, 1f64, 1f64)).unwrap();
+
+        assert_eq!(Some(Ordering::Greater), plane.side_certified(&Point3d::new(1f64, 1f64, 1f64)));
+        assert_eq!(Some(Ordering::Less), plane.side_certified(&Point3d::ORIGIN));
+        assert_eq!(Some(Ordering::Equal), plane.side_certified(&plane.origin));
+
+        let nearly_on = plane.point_at(0.3f64, 0.7f64, 0f64);
+        assert_eq!(None, plane.side_certified(&nearly_on));
+    }
+
+    #[test]
+    pub fn remap_round_trip() {
+        let plane = Plane::from_three_points(Point3d::new(1f64, 2f64, 3f64),
+                                             Point3d::new(1f64, 5f64, 3f64),
+                                             Point3d::new(1f64, 2f64, 8f64)).unwrap();
+        let point = Point3d::new(4f64, -2f64, 7f64);
+        let local = plane.remap_to_plane_space(&point);
+
+        assert!(plane.point_at(local.x, local.y, local.z).epsilon_equals(&point, &Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn project_and_lift_round_trip() {
+        let plane = Plane::from_point_normal(Point3d::new(1f64, 2f64, 3f64), Vector3d::new(1f64, 1f64, 1f64)).unwrap();
+        let point = plane.point_at(2.5f64, -4f64, 0f64);
+        let local = plane.project(&point);
+
+        assert!(local.epsilon_equals(&Point2d::new(2.5f64, -4f64), &Tolerance::DEFAULT));
+        assert!(plane.lift(&local).epsilon_equals(&point, &Tolerance::DEFAULT));
+
+        let vector = Vector2d::new(3f64, 1f64);
+        assert!(plane.project_vector(&plane.lift_vector(&vector)).epsilon_equals(&vector, &Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn flip_and_rotate() {
+        let mut plane = Plane::WORLDXY;
+        plane.flip();
+        assert!(plane.x == Vector3d::YAXIS);
+        assert!(plane.z == -Vector3d::ZAXIS);
+
+        let mut plane = Plane::WORLDXY;
+        plane.rotate_in_plane(std::f64::consts::FRAC_PI_2);
+        assert!(plane.x.epsilon_equals(&Vector3d::YAXIS, &Tolerance::DEFAULT));
+        assert!(plane.y.epsilon_equals(&-Vector3d::XAXIS, &Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn fit_to_points() {
+        let survey = [Point3d::new(0f64, 0f64, 1.01f64),
+                      Point3d::new(10f64, 0f64, 0.99f64),
+                      Point3d::new(10f64, 4f64, 1.01f64),
+                      Point3d::new(0f64, 4f64, 0.99f64),
+                      Point3d::new(5f64, 2f64, 1f64)];
+
+        let (plane, deviation) = Plane::fit_to_points(&survey).unwrap();
+        assert!(plane.z.is_parallel_to(&Vector3d::ZAXIS, &Tolerance::DEFAULT));
+        assert!(plane.x.is_parallel_to(&Vector3d::XAXIS, &Tolerance::DEFAULT));
+        assert!(plane.origin.epsilon_equals(&Point3d::new(5f64, 2f64, 1f64), &Tolerance::DEFAULT));
+        assert!(Tolerance::DEFAULT.equals(0.01f64, deviation));
+
+        let line = [Point3d::ORIGIN, Point3d::new(1f64, 0f64, 0f64), Point3d::new(2f64, 0f64, 0f64)];
+        assert_eq!(Err(PlaneError::Colinear), Plane::fit_to_points(&line).map(|_| ()));
+        assert_eq!(Err(PlaneError::TooFewPoints), Plane::fit_to_points(&line[..2]).map(|_| ()));
+    }
+
+    #[test]
+    pub fn transform_stretch_along_axes() {
+        let mut plane = Plane::WORLDXY;
+        let stretch = Transform::scale_non_uniform(&Plane::WORLDXY, 2f64, 3f64, 1f64);
+
+        assert!(plane.transform(&stretch).is_ok());
+        assert!(plane == Plane::WORLDXY);
+    }
+
+    #[test]
+    pub fn transform_shear_fails() {
+        let mut plane = Plane::WORLDXY;
+        let shear = Transform::shear(&Plane::WORLDXY, &Vector3d::XAXIS, &Vector3d::new(1f64, 1f64, 0f64), &Vector3d::ZAXIS);
+
+        assert_eq!(Err(TransformError::ShapeNotPreserved), plane.transform(&shear));
+        assert!(plane == Plane::WORLDXY);
+    }
 
 
 }
\ No newline at end of file
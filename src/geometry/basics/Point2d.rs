@@ -0,0 +1,125 @@
+use std::ops;
+
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Vector2d::Vector2d;
+
+/// A Point in two-dimensional space, such as a location on a floor plan or in a Plane
+#[derive(Copy, Clone)]
+pub struct Point2d
+{
+    /// The X coordinate
+    pub x: f64,
+    /// The Y coordinate
+    pub y: f64,
+}
+
+impl Point2d {
+
+    // Constants
+
+    /// The Origin, (0,0)
+    pub const ORIGIN:Point2d = Point2d { x:0f64, y:0f64 };
+
+    /// An Unset point. A completely invalid point that does not exist
+    pub const UNSET:Point2d = Point2d { x:f64::NAN, y:f64::NAN };
+
+    /// A Point at the edge of positive Infinity
+    pub const INFINITY:Point2d = Point2d { x:f64::INFINITY, y:f64::INFINITY };
+
+    /// A point at the edge of negative infinity
+    pub const NEGATIVE_INFINITY:Point2d = Point2d { x:f64::NEG_INFINITY, y:f64::NEG_INFINITY };
+
+    // Constructors
+
+    /// Constructs a new Point2d
+    pub fn new(x:f64, y:f64) -> Point2d {
+        Point2d { x, y }
+    }
+
+    // Methods
+
+    /// Returns the distance between the two points
+    pub fn distance_to(&self, rhs:&Point2d) -> f64 {
+        (rhs.x - self.x).hypot(rhs.y - self.y)
+    }
+
+    /// Returns the point at `parameter` along the way to `rhs`, where 0 is this point and 1 is `rhs`
+    pub fn interpolate(&self, rhs:&Point2d, parameter:f64) -> Point2d {
+        *self + (*rhs - *self) * parameter
+    }
+
+}
+
+impl ops::Sub<Point2d> for Point2d {
+    type Output = Vector2d;
+    fn sub(self, other: Point2d) -> Self::Output {
+        Vector2d::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl ops::Add<Vector2d> for Point2d {
+    type Output = Point2d;
+    fn add(self, motion: Vector2d) -> Self::Output {
+        Point2d::new(self.x + motion.x, self.y + motion.y)
+    }
+}
+
+impl ops::Sub<Vector2d> for Point2d {
+    type Output = Point2d;
+    fn sub(self, motion: Vector2d) -> Self::Output {
+        Point2d::new(self.x - motion.x, self.y - motion.y)
+    }
+}
+
+impl From<Vector2d> for Point2d {
+    fn from(vector: Vector2d) -> Self {
+        Point2d::new(vector.x, vector.y)
+    }
+}
+
+impl IsValid for Point2d {
+    fn is_valid(&self) -> bool {
+        self.x.is_finite() &&
+        self.y.is_finite()
+    }
+}
+
+impl EpsilonEquals for Point2d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.x, other.x) &&
+        tolerance.equals(self.y, other.y)
+    }
+}
+
+impl PartialEq for Point2d {
+    fn eq(&self, other: &Self) -> bool {
+        self.x.eq(&other.x) &&
+        self.y.eq(&other.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn is_valid() {
+        assert!(Point2d::ORIGIN.is_valid());
+        assert!(!Point2d::UNSET.is_valid());
+        assert!(!Point2d::INFINITY.is_valid());
+    }
+
+    #[test]
+    pub fn affine_arithmetic() {
+        let a = Point2d::new(1f64, 2f64);
+        let b = Point2d::new(4f64, 6f64);
+
+        assert_eq!(5f64, a.distance_to(&b));
+        assert!(b - a == Vector2d::new(3f64, 4f64));
+        assert!(a + (b - a) == b);
+        assert!(a.interpolate(&b, 0.5f64) == Point2d::new(2.5f64, 4f64));
+    }
+
+}
@@ -1,18 +1,23 @@
 use core::panic;
 use std::ops;
 
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::Transformable::Transformable;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::geometry::Transform::{Transform, TransformError};
 
 /// A Point in three-dimensional space
 #[derive(Copy, Clone)]
 pub struct Point3d
 {
     /// The X coordinate
-    pub x: f32,
+    pub x: f64,
     /// The Y coordinate
-    pub y: f32,
+    pub y: f64,
     /// The Z coordinate
-    pub z: f32,
+    pub z: f64,
 }
 
 impl Point3d {
@@ -20,27 +25,27 @@ impl Point3d {
     // Constants
     
     /// The Origin, (0,0,0)
-    pub const ORIGIN:Point3d = Point3d {x:0f32, y:0f32, z:0f32 };
+    pub const ORIGIN:Point3d = Point3d {x:0f64, y:0f64, z:0f64 };
 
     /// An Unset point. A completely invalid point that does not exist
-    pub const UNSET:Point3d = Point3d { x:f32::NAN, y:f32::NAN, z:f32::NAN };
+    pub const UNSET:Point3d = Point3d { x:f64::NAN, y:f64::NAN, z:f64::NAN };
 
     /// A Point at the edge of positive Infinity
-    pub const INFINITY:Point3d = Point3d { x:f32::INFINITY, y:f32::INFINITY, z:f32::INFINITY };
+    pub const INFINITY:Point3d = Point3d { x:f64::INFINITY, y:f64::INFINITY, z:f64::INFINITY };
 
     /// A point at the edge of negative infinity
-    pub const NEGATIVE_INFINITY:Point3d = Point3d { x:f32::NEG_INFINITY, y:f32::NEG_INFINITY, z:f32::NEG_INFINITY };
+    pub const NEGATIVE_INFINITY:Point3d = Point3d { x:f64::NEG_INFINITY, y:f64::NEG_INFINITY, z:f64::NEG_INFINITY };
 
     /// The Maximum possible Point
-    pub const MAX:Point3d = Point3d { x:f32::MAX, y:f32::MAX, z:f32::MAX };
+    pub const MAX:Point3d = Point3d { x:f64::MAX, y:f64::MAX, z:f64::MAX };
 
     /// The Minimum possible Point
-    pub const MIN:Point3d = Point3d { x:f32::MIN, y:f32::MIN, z:f32::MIN };
+    pub const MIN:Point3d = Point3d { x:f64::MIN, y:f64::MIN, z:f64::MIN };
 
     // Constructors
 
     /// Constructs a new Point3d
-    pub fn new(x:f32, y:f32, z:f32) -> Point3d {
+    pub fn new(x:f64, y:f64, z:f64) -> Point3d {
         Point3d { x, y, z }
     }
 
@@ -68,7 +73,7 @@ impl Point3d {
     }
 
     /// Multiplies a point by a factor
-    pub fn multiply_by_factor(p1: &Point3d, factor:f32) -> Point3d {
+    pub fn multiply_by_factor(p1: &Point3d, factor:f64) -> Point3d {
         Point3d::new(p1.x * factor,
             p1.y * factor,
             p1.z * factor)
@@ -82,48 +87,77 @@ impl Point3d {
     }
 
     /// Divides a point by a factor
-    pub fn divide_by_factor(p1: &Point3d, factor:f32) -> Point3d {
+    pub fn divide_by_factor(p1: &Point3d, factor:f64) -> Point3d {
         Point3d::new(p1.x / factor,
             p1.y / factor,
             p1.z / factor)
     }
 
-    pub fn distance_to(&self, rhs:&Point3d) -> f32 {
-        let x_val = (rhs.x - self.x).powf(2f32);
-        let y_val = (rhs.y - self.y).powf(2f32);
-        let z_val = (rhs.z - self.z).powf(2f32);
+    pub fn distance_to(&self, rhs:&Point3d) -> f64 {
+        let x_val = (rhs.x - self.x).powf(2f64);
+        let y_val = (rhs.y - self.y).powf(2f64);
+        let z_val = (rhs.z - self.z).powf(2f64);
         
         let val = x_val + y_val + z_val;
         
         val.sqrt()
     }
 
-    pub fn interpolate(&self, rhs:&Point3d, parameter:f32) -> Point3d {
-        let diff_x = (self.x - rhs.x) / 2f32;
-        let diff_y = (self.y - rhs.y) / 2f32;
-        let diff_z = (self.z - rhs.z) / 2f32;
-        
-        let new_x = self.x + (diff_x * parameter);
-        let new_y = self.y + (diff_y * parameter);
-        let new_z = self.z + (diff_z * parameter);
+    /// Returns the point at `parameter` along the way to `rhs`, where 0 is this point and 1 is `rhs`
+    pub fn interpolate(&self, rhs:&Point3d, parameter:f64) -> Point3d {
+        *self + (*rhs - *self) * parameter
+    }
 
-        Point3d::new(new_x, new_y, new_z)
+    /// Returns the weighted average of the points, the weights do not need to sum to one.
+    /// Returns None if there are no points, the counts differ or the weights cancel out
+    pub fn barycentric(points:&[Point3d], weights:&[f64]) -> Option<Point3d> {
+        if points.is_empty() || points.len() != weights.len() {
+            return None;
+        }
+
+        let total:f64 = weights.iter().sum();
+        if total == 0f64 || !total.is_finite() {
+            return None;
+        }
+
+        // Offsets from the first point keep the combination affine, and precise far from the origin
+        let base = points[0];
+        let mut offset = Vector3d::ORIGIN;
+        for (point, weight) in points.iter().zip(weights.iter()) {
+            offset = offset + (*point - base) * (weight / total);
+        }
+
+        Some(base + offset)
     }
 
 }
 
 
-impl ops::Add<Point3d> for Point3d {
+impl ops::Sub<Point3d> for Point3d {
+    type Output = Vector3d;
+    /// The vector that moves `rhs` onto this point
+    fn sub(self, rhs: Point3d) -> Self::Output {
+        Vector3d::new(self.x - rhs.x,
+                    self.y - rhs.y,
+                    self.z - rhs.z)
+    }
+}
+
+impl ops::Add<Vector3d> for Point3d {
     type Output = Point3d;
-    fn add(self, rhs: Point3d) -> Self::Output {
-        Point3d::add(&self, &rhs)
+    fn add(self, rhs: Vector3d) -> Self::Output {
+        Point3d::new(self.x + rhs.x,
+                    self.y + rhs.y,
+                    self.z + rhs.z)
     }
 }
 
-impl ops::Sub<Point3d> for Point3d {
+impl ops::Sub<Vector3d> for Point3d {
     type Output = Point3d;
-    fn sub(self, rhs: Point3d) -> Self::Output {
-        Point3d::subtract(&self, &rhs)
+    fn sub(self, rhs: Vector3d) -> Self::Output {
+        Point3d::new(self.x - rhs.x,
+                    self.y - rhs.y,
+                    self.z - rhs.z)
     }
 }
 
@@ -141,6 +175,42 @@ impl ops::Div<Point3d> for Point3d {
     }
 }
 
+impl From<Vector3d> for Point3d {
+    /// The point reached by moving from the origin along the vector
+    fn from(vector: Vector3d) -> Self {
+        Point3d::new(vector.x, vector.y, vector.z)
+    }
+}
+
+impl From<Point3d> for Vector3d {
+    /// The vector from the origin to the point
+    fn from(point: Point3d) -> Self {
+        Vector3d::new(point.x, point.y, point.z)
+    }
+}
+
+impl Transformable for Point3d {
+    /// Transforms the point, including any translation and the perspective divide
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        if !xform.is_valid() {
+            return Err(TransformError::Invalid);
+        }
+
+        let m = &xform.m;
+        let (x, y, z) = (self.x, self.y, self.z);
+
+        let w = m[3][0] * x + m[3][1] * y + m[3][2] * z + m[3][3];
+        if w == 0f64 {
+            return Err(TransformError::Degenerate);
+        }
+
+        self.x = (m[0][0] * x + m[0][1] * y + m[0][2] * z + m[0][3]) / w;
+        self.y = (m[1][0] * x + m[1][1] * y + m[1][2] * z + m[1][3]) / w;
+        self.z = (m[2][0] * x + m[2][1] * y + m[2][2] * z + m[2][3]) / w;
+        Ok(())
+    }
+}
+
 /*
 impl Clone for Point3d {
     fn clone(&self) -> Point3d {
@@ -157,16 +227,20 @@ impl IsValid for Point3d {
     }
 }
 
+impl EpsilonEquals for Point3d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.x, other.x) &&
+        tolerance.equals(self.y, other.y) &&
+        tolerance.equals(self.z, other.z)
+    }
+}
+
 impl PartialEq for Point3d {
     fn eq(&self, other: &Self) -> bool {
         self.x == other.x &&
         self.y == other.y &&
         self.z == other.z
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
-    }
 }
 
 impl PartialOrd for Point3d {
@@ -183,7 +257,7 @@ impl PartialOrd for Point3d {
     }
 
     /// So. How does this work?
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, _other: &Self) -> Option<std::cmp::Ordering> {
         panic!("not implemented yet")
     }
 }
@@ -208,73 +282,132 @@ mod tests {
     #[test]
     pub fn point_origin() {
         let point = Point3d::ORIGIN;
-        assert_eq!(0f32, point.x);
-        assert_eq!(0f32, point.y);
-        assert_eq!(0f32, point.z);
+        assert_eq!(0f64, point.x);
+        assert_eq!(0f64, point.y);
+        assert_eq!(0f64, point.z);
     }
 
     #[test]
     pub fn add_two_points() {
-        let point: Point3d = Point3d::new(100f32, 100f32, 100f32);
-        let point2: Point3d = Point3d::new(200f32, 200f32, 200f32);
+        let point: Point3d = Point3d::new(100f64, 100f64, 100f64);
+        let point2: Point3d = Point3d::new(200f64, 200f64, 200f64);
 
-        let new_point = point + point2;
-        assert_eq!(300f32, new_point.x);
-        assert_eq!(300f32, new_point.y);
-        assert_eq!(300f32, new_point.z);
+        let new_point = Point3d::add(&point, &point2);
+        assert_eq!(300f64, new_point.x);
+        assert_eq!(300f64, new_point.y);
+        assert_eq!(300f64, new_point.z);
     }
 
     #[test]
     pub fn subtract_two_points() {
-        let point: Point3d = Point3d::new(100f32, 100f32, 100f32);
-        let point2: Point3d = Point3d::new(200f32, 200f32, 200f32);
+        let point: Point3d = Point3d::new(100f64, 100f64, 100f64);
+        let point2: Point3d = Point3d::new(200f64, 200f64, 200f64);
+
+        let new_vector:Vector3d = point2 - point;
+        assert_eq!(100f64, new_vector.x);
+        assert_eq!(100f64, new_vector.y);
+        assert_eq!(100f64, new_vector.z);
+    }
+
+    #[test]
+    pub fn add_and_subtract_vector() {
+        let point = Point3d::new(1f64, 2f64, 3f64);
+        let vector = Vector3d::new(10f64, 20f64, 30f64);
+
+        assert!(point + vector == Point3d::new(11f64, 22f64, 33f64));
+        assert!(point - vector == Point3d::new(-9f64, -18f64, -27f64));
+        assert!((point + vector) - point == vector);
+    }
+
+    #[test]
+    pub fn conversions() {
+        let point = Point3d::new(1f64, 2f64, 3f64);
+        let vector = Vector3d::from(point);
+        assert!(Point3d::from(vector) == point);
+    }
+
+    #[test]
+    pub fn interpolate() {
+        let start = Point3d::new(0f64, 0f64, 0f64);
+        let end = Point3d::new(10f64, 20f64, 30f64);
+
+        assert!(start.interpolate(&end, 0.5f64) == Point3d::new(5f64, 10f64, 15f64));
+        assert!(start.interpolate(&end, 1f64) == end);
+    }
 
-        let new_point = point2 - point;
-        assert_eq!(100f32, new_point.x);
-        assert_eq!(100f32, new_point.y);
-        assert_eq!(100f32, new_point.z);
+    #[test]
+    pub fn barycentric() {
+        let points = [Point3d::new(0f64, 0f64, 0f64), Point3d::new(3f64, 0f64, 0f64), Point3d::new(0f64, 3f64, 0f64)];
+
+        let centroid = Point3d::barycentric(&points, &[1f64, 1f64, 1f64]).unwrap();
+        assert!(centroid.epsilon_equals(&Point3d::new(1f64, 1f64, 0f64), &Tolerance::DEFAULT));
+
+        let weighted = Point3d::barycentric(&points, &[0f64, 2f64, 2f64]).unwrap();
+        assert!(weighted.epsilon_equals(&Point3d::new(1.5f64, 1.5f64, 0f64), &Tolerance::DEFAULT));
+
+        assert!(Point3d::barycentric(&points, &[1f64, -1f64, 0f64]).is_none());
+        assert!(Point3d::barycentric(&points, &[1f64]).is_none());
     }
 
     #[test]
     pub fn divide_two_points() {
-        let point: Point3d = Point3d::new(100f32, 100f32, 100f32);
-        let point2: Point3d = Point3d::new(200f32, 200f32, 200f32);
+        let point: Point3d = Point3d::new(100f64, 100f64, 100f64);
+        let point2: Point3d = Point3d::new(200f64, 200f64, 200f64);
 
         let new_point = point2 / point;
-        assert_eq!(2f32, new_point.x);
-        assert_eq!(2f32, new_point.y);
-        assert_eq!(2f32, new_point.z);
+        assert_eq!(2f64, new_point.x);
+        assert_eq!(2f64, new_point.y);
+        assert_eq!(2f64, new_point.z);
     }
 
     #[test]
     pub fn divide_by_factor() {
-        let point = Point3d::new(100f32, 200f32, 300f32);
-        let new_point = Point3d::divide_by_factor(&point, 2f32);
+        let point = Point3d::new(100f64, 200f64, 300f64);
+        let new_point = Point3d::divide_by_factor(&point, 2f64);
         
-        assert_eq!(50f32, new_point.x);
-        assert_eq!(100f32, new_point.y);
-        assert_eq!(150f32, new_point.z);
+        assert_eq!(50f64, new_point.x);
+        assert_eq!(100f64, new_point.y);
+        assert_eq!(150f64, new_point.z);
     }
 
     #[test]
     pub fn multiply_two_points() {
-        let point: Point3d = Point3d::new(4f32, 4f32, 4f32);
-        let point2: Point3d = Point3d::new(5f32, 5f32, 5f32);
+        let point: Point3d = Point3d::new(4f64, 4f64, 4f64);
+        let point2: Point3d = Point3d::new(5f64, 5f64, 5f64);
 
         let new_point = point * point2;
-        assert_eq!(20f32, new_point.x);
-        assert_eq!(20f32, new_point.y);
-        assert_eq!(20f32, new_point.z);
+        assert_eq!(20f64, new_point.x);
+        assert_eq!(20f64, new_point.y);
+        assert_eq!(20f64, new_point.z);
     }
 
     #[test]
     pub fn multiply_by_factor() {
-        let point: Point3d = Point3d::new(100f32, 200f32, 300f32);
-        let bigger_point = Point3d::multiply_by_factor(&point, 2f32);
+        let point: Point3d = Point3d::new(100f64, 200f64, 300f64);
+        let bigger_point = Point3d::multiply_by_factor(&point, 2f64);
         
-        assert_eq!(200f32, bigger_point.x);
-        assert_eq!(400f32, bigger_point.y);
-        assert_eq!(600f32, bigger_point.z);
+        assert_eq!(200f64, bigger_point.x);
+        assert_eq!(400f64, bigger_point.y);
+        assert_eq!(600f64, bigger_point.z);
+    }
+
+    #[test]
+    pub fn translate() {
+        let mut point = Point3d::new(1f64, 2f64, 3f64);
+        point.translate(&Vector3d::new(1f64, 1f64, 1f64)).unwrap();
+
+        assert_eq!(2f64, point.x);
+        assert_eq!(3f64, point.y);
+        assert_eq!(4f64, point.z);
+    }
+
+    #[test]
+    pub fn epsilon_equals() {
+        let point = Point3d::new(0.1f64, 0.2f64, 0.3f64);
+        let summed = Point3d::add(&Point3d::new(0.05f64, 0.1f64, 0.1f64), &Point3d::new(0.05f64, 0.1f64, 0.2f64));
+
+        assert!(point.epsilon_equals(&summed, &Tolerance::DEFAULT));
+        assert!(!point.epsilon_equals(&Point3d::new(0.1f64, 0.2f64, 0.31f64), &Tolerance::DEFAULT));
     }
 
 }
@@ -0,0 +1,94 @@
+use crate::IsValid::IsValid;
+use crate::geometry::basics::Point3d::Point3d;
+
+/// A single precision Point in three-dimensional space.
+/// Intended for handing geometry to the GPU, all modelling should be done with a Point3d
+#[derive(Copy, Clone)]
+pub struct Point3f
+{
+    /// The X coordinate
+    pub x: f32,
+    /// The Y coordinate
+    pub y: f32,
+    /// The Z coordinate
+    pub z: f32,
+}
+
+impl Point3f {
+
+    // Constants
+
+    /// The Origin, (0,0,0)
+    pub const ORIGIN:Point3f = Point3f { x:0f32, y:0f32, z:0f32 };
+
+    /// An Unset point. A completely invalid point that does not exist
+    pub const UNSET:Point3f = Point3f { x:f32::NAN, y:f32::NAN, z:f32::NAN };
+
+    // Constructors
+
+    /// Constructs a new Point3f
+    pub fn new(x:f32, y:f32, z:f32) -> Point3f {
+        Point3f { x, y, z }
+    }
+
+}
+
+impl From<Point3d> for Point3f {
+    /// Narrows a double precision point, rounding each coordinate to the nearest f32
+    fn from(point: Point3d) -> Self {
+        Point3f::new(point.x as f32, point.y as f32, point.z as f32)
+    }
+}
+
+impl From<Point3f> for Point3d {
+    /// Widens a single precision point, this is lossless
+    fn from(point: Point3f) -> Self {
+        Point3d::new(point.x as f64, point.y as f64, point.z as f64)
+    }
+}
+
+impl IsValid for Point3f {
+    fn is_valid(&self) -> bool {
+        self.x.is_finite() &&
+        self.y.is_finite() &&
+        self.z.is_finite()
+    }
+}
+
+impl PartialEq for Point3f {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x &&
+        self.y == other.y &&
+        self.z == other.z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn is_valid() {
+        assert!(!Point3f::UNSET.is_valid());
+        assert!(Point3f::ORIGIN.is_valid());
+    }
+
+    #[test]
+    pub fn round_trip() {
+        let single = Point3f::new(1.5f32, -2.25f32, 1000f32);
+        let double = Point3d::from(single);
+        assert_eq!(1.5f64, double.x);
+        assert_eq!(-2.25f64, double.y);
+        assert_eq!(1000f64, double.z);
+
+        assert!(Point3f::from(double) == single);
+    }
+
+    #[test]
+    pub fn narrowing_rounds() {
+        let precise = Point3d::new(1_000_000.001f64, 0f64, 0f64);
+        let single = Point3f::from(precise);
+        assert_eq!(1_000_000f32, single.x);
+    }
+
+}
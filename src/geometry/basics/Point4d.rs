@@ -0,0 +1,198 @@
+use std::ops;
+
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::Transformable::Transformable;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::Transform::{Transform, TransformError};
+
+/// A Point in homogeneous coordinates, as used for the weighted control points of rational curves
+/// and for projective transforms. The euclidean point it stands for is (x/w, y/w, z/w),
+/// so x, y and z are already multiplied by the weight.
+/// A weight of zero is a point at infinity in the direction (x, y, z)
+#[derive(Copy, Clone)]
+pub struct Point4d
+{
+    /// The weighted X coordinate
+    pub x: f64,
+    /// The weighted Y coordinate
+    pub y: f64,
+    /// The weighted Z coordinate
+    pub z: f64,
+    /// The weight
+    pub w: f64,
+}
+
+impl Point4d {
+
+    // Constants
+
+    /// All zeros, which stands for no point at all but is the starting value of a weighted sum
+    pub const ZERO:Point4d = Point4d { x:0f64, y:0f64, z:0f64, w:0f64 };
+
+    /// An Unset point. A completely invalid point that does not exist
+    pub const UNSET:Point4d = Point4d { x:f64::NAN, y:f64::NAN, z:f64::NAN, w:f64::NAN };
+
+    // Constructors
+
+    /// Constructs a new Point4d from homogeneous coordinates
+    pub fn new(x:f64, y:f64, z:f64, w:f64) -> Point4d {
+        Point4d { x, y, z, w }
+    }
+
+    /// Constructs the homogeneous form of a euclidean point with a weight, (x*w, y*w, z*w, w)
+    pub fn from_weighted(point:&Point3d, weight:f64) -> Point4d {
+        Point4d::new(point.x * weight, point.y * weight, point.z * weight, weight)
+    }
+
+    // Methods
+
+    /// Returns the euclidean point by performing the perspective divide.
+    /// Returns None for a point at infinity, whose weight is zero
+    pub fn euclidean(&self) -> Option<Point3d> {
+        if self.w == 0f64 {
+            return None;
+        }
+
+        Some(Point3d::new(self.x / self.w, self.y / self.w, self.z / self.w))
+    }
+
+    /// Returns the point at `parameter` along the way to `rhs` in homogeneous space, where 0 is this point and 1 is `rhs`.
+    /// The euclidean result is pulled towards whichever end has the greater weight
+    pub fn interpolate(&self, rhs:&Point4d, parameter:f64) -> Point4d {
+        *self * (1f64 - parameter) + *rhs * parameter
+    }
+
+}
+
+impl ops::Add<Point4d> for Point4d {
+    type Output = Point4d;
+    fn add(self, other: Point4d) -> Self::Output {
+        Point4d::new(self.x + other.x, self.y + other.y, self.z + other.z, self.w + other.w)
+    }
+}
+
+impl ops::Sub<Point4d> for Point4d {
+    type Output = Point4d;
+    fn sub(self, other: Point4d) -> Self::Output {
+        Point4d::new(self.x - other.x, self.y - other.y, self.z - other.z, self.w - other.w)
+    }
+}
+
+/// Scales every coordinate including the weight, which leaves the euclidean point unchanged
+impl ops::Mul<f64> for Point4d {
+    type Output = Point4d;
+    fn mul(self, factor: f64) -> Self::Output {
+        Point4d::new(self.x * factor, self.y * factor, self.z * factor, self.w * factor)
+    }
+}
+
+impl ops::Div<f64> for Point4d {
+    type Output = Point4d;
+    fn div(self, factor: f64) -> Self::Output {
+        Point4d::new(self.x / factor, self.y / factor, self.z / factor, self.w / factor)
+    }
+}
+
+/// A euclidean point has a weight of one
+impl From<Point3d> for Point4d {
+    fn from(point: Point3d) -> Self {
+        Point4d::from_weighted(&point, 1f64)
+    }
+}
+
+impl IsValid for Point4d {
+    /// A valid point is finite and is not all zeros
+    fn is_valid(&self) -> bool {
+        self.x.is_finite() &&
+        self.y.is_finite() &&
+        self.z.is_finite() &&
+        self.w.is_finite() &&
+        !(self.x == 0f64 && self.y == 0f64 && self.z == 0f64 && self.w == 0f64)
+    }
+}
+
+impl Transformable for Point4d {
+    /// Multiplies all four coordinates by the matrix. No divide is needed in homogeneous space,
+    /// so unlike Point3d a point sent to infinity by a perspective transform is not an error
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        if !xform.is_valid() {
+            return Err(TransformError::Invalid);
+        }
+
+        let m = &xform.m;
+        let v = [self.x, self.y, self.z, self.w];
+        let row = |r:usize| m[r].iter().zip(v).map(|(a, b)| a * b).sum::<f64>();
+
+        *self = Point4d::new(row(0), row(1), row(2), row(3));
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Point4d {
+    /// Compares the homogeneous coordinates, so the same euclidean point with different weights is not equal
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.x, other.x) &&
+        tolerance.equals(self.y, other.y) &&
+        tolerance.equals(self.z, other.z) &&
+        tolerance.equals(self.w, other.w)
+    }
+}
+
+impl PartialEq for Point4d {
+    fn eq(&self, other: &Self) -> bool {
+        self.x.eq(&other.x) &&
+        self.y.eq(&other.y) &&
+        self.z.eq(&other.z) &&
+        self.w.eq(&other.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    pub fn euclidean_round_trip() {
+        let point = Point3d::new(1f64, -2f64, 3f64);
+        let weighted = Point4d::from_weighted(&point, 0.5f64);
+
+        assert!(weighted == Point4d::new(0.5f64, -1f64, 1.5f64, 0.5f64));
+        assert!(weighted.euclidean().unwrap().epsilon_equals(&point, &Tolerance::DEFAULT));
+        assert!((weighted * 4f64).euclidean().unwrap().epsilon_equals(&point, &Tolerance::DEFAULT));
+        assert!(Point4d::from(point).w == 1f64);
+        assert!(Point4d::new(1f64, 0f64, 0f64, 0f64).euclidean().is_none());
+        assert!(!Point4d::ZERO.is_valid());
+        assert!(!Point4d::UNSET.is_valid());
+    }
+
+    #[test]
+    pub fn weighted_interpolation() {
+        let a = Point4d::from_weighted(&Point3d::ORIGIN, 1f64);
+        let b = Point4d::from_weighted(&Point3d::new(4f64, 0f64, 0f64), 3f64);
+
+        // Halfway in homogeneous space lands three quarters of the way to the heavier end
+        let mid = a.interpolate(&b, 0.5f64).euclidean().unwrap();
+        assert!(mid.epsilon_equals(&Point3d::new(3f64, 0f64, 0f64), &Tolerance::DEFAULT));
+    }
+
+    #[test]
+    pub fn perspective_divide() {
+        let perspective = Transform::perspective(PI / 2f64, 1f64, 1f64, 100f64);
+
+        let mut euclidean = Point3d::new(1f64, 1f64, -1f64);
+        let mut homogeneous = Point4d::from_weighted(&euclidean, 2f64);
+        euclidean.transform(&perspective).unwrap();
+        homogeneous.transform(&perspective).unwrap();
+        assert!(homogeneous.euclidean().unwrap().epsilon_equals(&euclidean, &Tolerance::DEFAULT));
+
+        // On the camera plane a Point3d cannot be divided, but a Point4d goes to infinity
+        let mut camera = Point4d::from(Point3d::new(1f64, 0f64, 0f64));
+        camera.transform(&perspective).unwrap();
+        assert_eq!(0f64, camera.w);
+        assert!(camera.euclidean().is_none());
+    }
+
+}
@@ -1,7 +1,17 @@
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
 
+use crate::geometry::basics::BoundingBox::BoundingBox;
+use crate::geometry::basics::Interval::Interval;
+use crate::geometry::basics::Line::Line;
+use crate::geometry::basics::Plane::Plane;
 use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::Transformable::Transformable;
+use crate::geometry::Transform::{Transform, TransformError};
 
+#[derive(Clone)]
 pub struct PolyLine {
     points:Vec<Point3d>,
 }
@@ -14,15 +24,15 @@ impl PolyLine {
         PolyLine { points }
     }
 
-    pub fn length(&self) -> f32 {
-        if self.points.len() == 0
+    pub fn length(&self) -> f64 {
+        if self.points.is_empty()
         {
-            return 0f32
+            return 0f64
         }
 
         let mut last_point:Point3d = self.points[0];
         let mut counter = 0;
-        let mut length = 0f32;
+        let mut length = 0f64;
         loop {
             if counter == self.points.len() {
                 break;
@@ -39,12 +49,410 @@ impl PolyLine {
         length
     }
 
+    /// Returns the vertices of the polyline in order
+    pub fn points(&self) -> &[Point3d] {
+        &self.points
+    }
+
+    /// Returns the number of vertices
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Adds a vertex to the end of the polyline
+    pub fn push(&mut self, point:Point3d) {
+        self.points.push(point);
+    }
+
+    /// Inserts a vertex before the vertex at `index`, shifting the rest along.
+    /// An index of `point_count()` adds it at the end
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than `point_count()`
+    pub fn insert(&mut self, index:usize, point:Point3d) {
+        self.points.insert(index, point);
+    }
+
+    /// Removes and returns the vertex at `index`, or None if there is no such vertex
+    pub fn remove(&mut self, index:usize) -> Option<Point3d> {
+        if index >= self.points.len() {
+            return None;
+        }
+
+        Some(self.points.remove(index))
+    }
+
+    /// Returns the number of straight segments between the vertices
+    pub fn segment_count(&self) -> usize {
+        self.points.len().saturating_sub(1)
+    }
+
+    /// Returns the segment from vertex `index` to the next, or None if there is no such segment
+    pub fn segment_at(&self, index:usize) -> Option<Line> {
+        if index >= self.segment_count() {
+            return None;
+        }
+
+        Some(Line::new(self.points[index], self.points[index + 1]))
+    }
+
+    /// Returns the straight segments between consecutive vertices
+    pub fn segments(&self) -> impl Iterator<Item = Line> + '_ {
+        self.points.windows(2).map(|pair| Line::new(pair[0], pair[1]))
+    }
+
+    /// Returns the range of parameters the polyline is defined over.
+    /// Parameters are normalized by length, so 0 is the start, 1 is the end and 0.5 is halfway along
+    pub fn domain(&self) -> Interval {
+        Interval::UNIT
+    }
+
+    /// Returns the point at the normalized parameter `t`, the fraction of the length along the polyline.
+    /// Parameters outside the domain are clamped to it, and a polyline without length returns its first point
+    pub fn point_at(&self, t:f64) -> Point3d {
+        let Some(first) = self.points.first() else {
+            return Point3d::UNSET;
+        };
+
+        let length = self.length();
+        if length == 0f64 {
+            return *first;
+        }
+
+        let mut remaining = t.clamp(0f64, 1f64) * length;
+        for segment in self.segments() {
+            let segment_length = segment.length();
+            if remaining <= segment_length && segment_length > 0f64 {
+                return segment.point_at(remaining / segment_length);
+            }
+            remaining -= segment_length;
+        }
+
+        self.points[self.points.len() - 1]
+    }
+
+    /// Returns the normalized parameter of the closest point on the polyline to `point`
+    pub fn closest_parameter(&self, point:&Point3d) -> f64 {
+        let length = self.length();
+        if length == 0f64 {
+            return 0f64;
+        }
+
+        let mut best = (f64::INFINITY, 0f64);
+        let mut start = 0f64;
+        for segment in self.segments() {
+            let t = segment.closest_parameter(point).clamp(0f64, 1f64);
+            let distance = segment.point_at(t).distance_to(point);
+            if distance < best.0 {
+                best = (distance, start + t * segment.length());
+            }
+            start += segment.length();
+        }
+
+        best.1 / length
+    }
+
+    /// Returns the closest point on the polyline to `point`
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        if self.segment_count() == 0 {
+            return self.points.first().copied().unwrap_or(Point3d::UNSET);
+        }
+
+        self.segments()
+            .map(|segment| segment.closest_point(point, true))
+            .min_by(|a, b| a.distance_to(point).total_cmp(&b.distance_to(point)))
+            .unwrap_or(Point3d::UNSET)
+    }
+
+    /// Returns true if the polyline ends within tolerance of where it starts, and encloses at least a triangle
+    pub fn is_closed(&self, tolerance:&Tolerance) -> bool {
+        self.points.len() >= 4 &&
+        self.points[0].epsilon_equals(&self.points[self.points.len() - 1], tolerance)
+    }
+
+    /// Closes the polyline. If it already ends within tolerance of its start the last vertex is snapped onto the start,
+    /// otherwise a copy of the start is added to the end
+    pub fn close(&mut self, tolerance:&Tolerance) {
+        let (Some(first), Some(last)) = (self.points.first().copied(), self.points.last().copied()) else {
+            return;
+        };
+
+        if self.points.len() > 1 && first.epsilon_equals(&last, tolerance) {
+            let index = self.points.len() - 1;
+            self.points[index] = first;
+        } else {
+            self.points.push(first);
+        }
+    }
+
+    /// Reverses the order of the vertices, so the polyline runs from its end to its start
+    pub fn reverse(&mut self) {
+        self.points.reverse();
+    }
+
+    /// Returns the smallest box containing every vertex
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(self.points.iter().copied())
+    }
+
+    /// Returns the centroid of the polyline as a wire, the average of its segment midpoints weighted by their length.
+    /// A polyline without length returns the average of its vertices
+    pub fn centroid(&self) -> Point3d {
+        let midpoints:Vec<Point3d> = self.segments().map(|segment| segment.midpoint()).collect();
+        let lengths:Vec<f64> = self.segments().map(|segment| segment.length()).collect();
+
+        Point3d::barycentric(&midpoints, &lengths)
+            .or_else(|| Point3d::barycentric(&self.points, &vec![1f64; self.points.len()]))
+            .unwrap_or(Point3d::UNSET)
+    }
+
+    /// Returns the enclosed area of a closed planar polyline.
+    /// Returns None if the polyline is not closed or strays further than the tolerance from a plane
+    pub fn area(&self, tolerance:&Tolerance) -> Option<f64> {
+        if !self.is_closed(tolerance) {
+            return None;
+        }
+
+        let (_, deviation) = Plane::fit_to_points(&self.points[1..]).ok()?;
+        if deviation > tolerance.absolute {
+            return None;
+        }
+
+        Some(self.vector_area().length())
+    }
+
+    /// Returns which way a closed polyline winds when seen looking down the plane's Z axis.
+    /// Returns None if it is not closed or encloses no area in the plane
+    pub fn orientation(&self, plane:&Plane, tolerance:&Tolerance) -> Option<Orientation> {
+        if !self.is_closed(tolerance) {
+            return None;
+        }
+
+        let signed_area = self.vector_area().dot(&Vector3d::unitize(&plane.z));
+        if tolerance.is_zero(signed_area) {
+            None
+        } else if signed_area > 0f64 {
+            Some(Orientation::Anticlockwise)
+        } else {
+            Some(Orientation::Clockwise)
+        }
+    }
+
+    /// Returns a simplified copy using the Douglas–Peucker algorithm, keeping the fewest vertices
+    /// such that no original vertex is further than `max_deviation` from the result. The ends are always kept
+    /// https://en.wikipedia.org/wiki/Ramer%E2%80%93Douglas%E2%80%93Peucker_algorithm
+    pub fn simplify_douglas_peucker(&self, max_deviation:f64) -> PolyLine {
+        if self.points.len() < 3 {
+            return self.clone();
+        }
+
+        let mut keep = vec![false; self.points.len()];
+        keep[0] = true;
+        keep[self.points.len() - 1] = true;
+
+        let mut ranges = vec![(0, self.points.len() - 1)];
+        while let Some((start, end)) = ranges.pop() {
+            let chord = Line::new(self.points[start], self.points[end]);
+            let furthest = (start + 1..end)
+                .map(|index| (index, chord.distance_to(&self.points[index])))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((index, distance)) = furthest {
+                if distance > max_deviation {
+                    keep[index] = true;
+                    ranges.push((start, index));
+                    ranges.push((index, end));
+                }
+            }
+        }
+
+        PolyLine::new(self.points.iter().zip(keep).filter(|(_, keep)| *keep).map(|(point, _)| *point).collect())
+    }
+
+    /// Returns a simplified copy using the Visvalingam–Whyatt algorithm, repeatedly removing the vertex
+    /// whose triangle with its neighbours has the least area until every remaining triangle is at least `min_area`.
+    /// The ends are always kept
+    /// https://en.wikipedia.org/wiki/Visvalingam%E2%80%93Whyatt_algorithm
+    pub fn simplify_visvalingam_whyatt(&self, min_area:f64) -> PolyLine {
+        let mut points = self.points.clone();
+        let triangle_area = |points:&[Point3d], index:usize| {
+            let (a, b, c) = (points[index - 1], points[index], points[index + 1]);
+            (b - a).cross(&(c - a)).length() * 0.5f64
+        };
+
+        let mut areas:Vec<f64> = (1..points.len().saturating_sub(1)).map(|index| triangle_area(&points, index)).collect();
+        while let Some((smallest, area)) = areas.iter().copied().enumerate().min_by(|a, b| a.1.total_cmp(&b.1)) {
+            if area >= min_area {
+                break;
+            }
+
+            // areas[i] belongs to points[i + 1], so only the neighbours of the removed vertex change
+            points.remove(smallest + 1);
+            areas.remove(smallest);
+            if smallest > 0 {
+                areas[smallest - 1] = triangle_area(&points, smallest);
+            }
+            if smallest < areas.len() {
+                areas[smallest] = triangle_area(&points, smallest + 1);
+            }
+        }
+
+        PolyLine::new(points)
+    }
+
+    /// Returns a copy without any vertex that is within tolerance of the vertex before it
+    pub fn remove_duplicates(&self, tolerance:&Tolerance) -> PolyLine {
+        let mut points:Vec<Point3d> = Vec::with_capacity(self.points.len());
+        for point in &self.points {
+            if points.last().is_none_or(|last| !last.epsilon_equals(point, tolerance)) {
+                points.push(*point);
+            }
+        }
+
+        PolyLine::new(points)
+    }
+
+    /// Returns a copy without any interior vertex that lies within tolerance of the straight line past it.
+    /// A vertex where the polyline doubles back on itself is kept. The ends are always kept
+    pub fn remove_collinear(&self, tolerance:&Tolerance) -> PolyLine {
+        let mut points:Vec<Point3d> = Vec::with_capacity(self.points.len());
+        for (index, point) in self.points.iter().enumerate() {
+            let Some(next) = self.points.get(index + 1) else {
+                points.push(*point);
+                break;
+            };
+
+            let redundant = points.last()
+                .is_some_and(|previous| Line::new(*previous, *next).distance_to(point) <= tolerance.absolute);
+            if !redundant {
+                points.push(*point);
+            }
+        }
+
+        PolyLine::new(points)
+    }
+
+    /// Returns a copy with vertices at equal distances along the polyline, as close to `spacing` apart as fits its length exactly.
+    /// Corners between the new vertices are cut. Returns None if the spacing is not positive or the polyline has no length
+    pub fn resample_by_spacing(&self, spacing:f64) -> Option<PolyLine> {
+        let length = self.length();
+        if spacing.is_nan() || spacing <= 0f64 || length == 0f64 {
+            return None;
+        }
+
+        let segments = (length / spacing).round().max(1f64) as usize;
+        self.resample_by_count(segments + 1)
+    }
+
+    /// Returns a copy with `count` vertices spread at equal distances along the polyline, including both ends.
+    /// Corners between the new vertices are cut. Returns None if the count is below two or the polyline has no length
+    pub fn resample_by_count(&self, count:usize) -> Option<PolyLine> {
+        let length = self.length();
+        if count < 2 || length == 0f64 {
+            return None;
+        }
+
+        let mut points = Vec::with_capacity(count);
+        let mut segments = self.segments();
+        let mut segment = segments.next()?;
+        let mut segment_start = 0f64;
+        for index in 0..count - 1 {
+            let target = length * index as f64 / (count - 1) as f64;
+            while target > segment_start + segment.length() {
+                segment_start += segment.length();
+                match segments.next() {
+                    Some(next) => segment = next,
+                    None => break,
+                }
+            }
+
+            let t = if segment.length() == 0f64 { 0f64 } else { (target - segment_start) / segment.length() };
+            points.push(segment.point_at(t.clamp(0f64, 1f64)));
+        }
+        points.push(self.points[self.points.len() - 1]);
+
+        Some(PolyLine::new(points))
+    }
+
+    /// Returns a copy rounded by `iterations` passes of Chaikin's corner cutting,
+    /// each replacing every segment with points a quarter and three quarters along it.
+    /// Open polylines keep their ends, and polylines closed by `close` stay closed with every corner cut
+    /// https://www.cs.unc.edu/~dm/UNC/COMP258/LECTURES/Chaikins-Algorithm.pdf
+    pub fn smooth_chaikin(&self, iterations:usize) -> PolyLine {
+        let closed = self.is_closed(&Tolerance::EXACT);
+        let mut points = self.points.clone();
+        for _ in 0..iterations {
+            if points.len() < 3 {
+                break;
+            }
+
+            let mut smoothed = Vec::with_capacity(points.len() * 2);
+            if !closed {
+                smoothed.push(points[0]);
+            }
+            for pair in points.windows(2) {
+                smoothed.push(pair[0].interpolate(&pair[1], 0.25f64));
+                smoothed.push(pair[0].interpolate(&pair[1], 0.75f64));
+            }
+            if closed {
+                smoothed.push(smoothed[0]);
+            } else {
+                smoothed.push(points[points.len() - 1]);
+            }
+
+            points = smoothed;
+        }
+
+        PolyLine::new(points)
+    }
+
+    /// Splits the polyline at every interior vertex where its direction turns by more than `angle` radians.
+    /// The pieces share their end vertices, and a polyline without such kinks is returned whole
+    pub fn split_at_kinks(&self, angle:f64) -> Vec<PolyLine> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        for index in 1..self.points.len().saturating_sub(1) {
+            let incoming = self.points[index] - self.points[index - 1];
+            let outgoing = self.points[index + 1] - self.points[index];
+            if incoming.angle_to(&outgoing) > angle {
+                pieces.push(PolyLine::new(self.points[start..=index].to_vec()));
+                start = index;
+            }
+        }
+
+        pieces.push(PolyLine::new(self.points[start..].to_vec()));
+        pieces
+    }
+
+    // Helpers
+
+    /// Returns the vector area of the polyline treated as closed: normal to its plane, as long as its area,
+    /// and pointing the way the right hand rule gives for its winding
+    fn vector_area(&self) -> Vector3d {
+        let Some(origin) = self.points.first() else {
+            return Vector3d::ORIGIN;
+        };
+
+        self.points.windows(2)
+            .map(|pair| (pair[0] - *origin).cross(&(pair[1] - *origin)))
+            .fold(Vector3d::ORIGIN, |sum, cross| sum + cross) * 0.5f64
+    }
+
+}
+
+/// Which way a closed curve winds around a plane's Z axis
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Orientation {
+    /// Winding the way the plane's X axis turns into its Y axis
+    Anticlockwise,
+    /// Winding the way the plane's Y axis turns into its X axis
+    Clockwise,
 }
 
 
 impl IsValid for PolyLine {
     fn is_valid(&self) -> bool {
-        if self.points.len() == 0 {
+        if self.points.is_empty() {
             return false;
         }
         
@@ -66,6 +474,26 @@ impl IsValid for PolyLine {
     }
 }
 
+impl EpsilonEquals for PolyLine {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.points.len() == other.points.len() &&
+        self.points.iter().zip(other.points.iter()).all(|(a, b)| a.epsilon_equals(b, tolerance))
+    }
+}
+
+impl Transformable for PolyLine {
+    /// Transforms every point of the polyline
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        let mut points = self.points.clone();
+        for point in points.iter_mut() {
+            point.transform(xform)?;
+        }
+
+        self.points = points;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -73,19 +501,147 @@ pub mod tests {
     #[test]
     fn test_distance_invalid() {
         let poly = PolyLine::UNSET;
-        assert_eq!(0f32, poly.length());
+        assert_eq!(0f64, poly.length());
     }
 
     #[test]
     fn test_distance_valid() {
         let points = vec!(Point3d::ORIGIN,
-                          Point3d::new(100f32, 0f32, 0f32),
-                          Point3d::new(100f32, 500f32, 0f32),
-                          Point3d::new(0f32, 500f32, 0f32),
+                          Point3d::new(100f64, 0f64, 0f64),
+                          Point3d::new(100f64, 500f64, 0f64),
+                          Point3d::new(0f64, 500f64, 0f64),
                           Point3d::ORIGIN);
         
         let poly = PolyLine::new(points);
-        assert_eq!(1200f32, poly.length());
+        assert_eq!(1200f64, poly.length());
+    }
+
+
+    fn square() -> PolyLine {
+        PolyLine::new(vec!(Point3d::ORIGIN,
+                           Point3d::new(2f64, 0f64, 0f64),
+                           Point3d::new(2f64, 2f64, 0f64),
+                           Point3d::new(0f64, 2f64, 0f64),
+                           Point3d::ORIGIN))
+    }
+
+    #[test]
+    fn mutation_and_segments() {
+        let mut poly = PolyLine::new(vec!(Point3d::ORIGIN, Point3d::new(2f64, 0f64, 0f64)));
+        poly.push(Point3d::new(2f64, 2f64, 0f64));
+        poly.insert(1, Point3d::new(1f64, 0f64, 0f64));
+
+        assert_eq!(4, poly.point_count());
+        assert_eq!(3, poly.segment_count());
+        assert!(poly.segment_at(1).unwrap() == Line::new(Point3d::new(1f64, 0f64, 0f64), Point3d::new(2f64, 0f64, 0f64)));
+        assert!(poly.segment_at(3).is_none());
+        assert!(poly.remove(1).unwrap() == Point3d::new(1f64, 0f64, 0f64));
+        assert!(poly.remove(5).is_none());
+        assert_eq!(0, PolyLine::UNSET.segment_count());
+    }
+
+    #[test]
+    fn evaluation_and_closest_point() {
+        let poly = square();
+
+        assert!(poly.point_at(0.375f64) == Point3d::new(2f64, 1f64, 0f64));
+        assert!(poly.point_at(2f64) == Point3d::ORIGIN);
+        assert!(poly.closest_point(&Point3d::new(3f64, 1f64, 5f64)) == Point3d::new(2f64, 1f64, 0f64));
+        assert_eq!(0.375f64, poly.closest_parameter(&Point3d::new(3f64, 1f64, 5f64)));
+        assert!(poly.centroid().epsilon_equals(&Point3d::new(1f64, 1f64, 0f64), &Tolerance::DEFAULT));
+        assert!(poly.bounding_box().max() == Point3d::new(2f64, 2f64, 0f64));
+    }
+
+    #[test]
+    fn closure_area_and_orientation() {
+        let mut poly = square();
+        assert!(poly.is_closed(&Tolerance::DEFAULT));
+        assert_eq!(Some(4f64), poly.area(&Tolerance::DEFAULT));
+        assert_eq!(Some(Orientation::Anticlockwise), poly.orientation(&Plane::WORLDXY, &Tolerance::DEFAULT));
+
+        poly.reverse();
+        assert_eq!(Some(Orientation::Clockwise), poly.orientation(&Plane::WORLDXY, &Tolerance::DEFAULT));
+
+        poly.remove(4);
+        assert!(!poly.is_closed(&Tolerance::DEFAULT));
+        assert_eq!(None, poly.area(&Tolerance::DEFAULT));
+        poly.close(&Tolerance::DEFAULT);
+        assert!(poly.is_closed(&Tolerance::EXACT));
+
+        let mut bent = square();
+        bent.insert(2, Point3d::new(2f64, 1f64, 1f64));
+        assert_eq!(None, bent.area(&Tolerance::DEFAULT));
+    }
+
+
+    #[test]
+    fn simplification() {
+        let noisy = PolyLine::new(vec!(Point3d::ORIGIN,
+                                       Point3d::new(1f64, 0.01f64, 0f64),
+                                       Point3d::new(2f64, -0.01f64, 0f64),
+                                       Point3d::new(3f64, 0f64, 0f64),
+                                       Point3d::new(3f64, 2f64, 0f64)));
+        let corner = PolyLine::new(vec!(Point3d::ORIGIN, Point3d::new(3f64, 0f64, 0f64), Point3d::new(3f64, 2f64, 0f64)));
+
+        assert!(noisy.simplify_douglas_peucker(0.1f64).epsilon_equals(&corner, &Tolerance::EXACT));
+        assert_eq!(5, noisy.simplify_douglas_peucker(0.001f64).point_count());
+        assert!(noisy.simplify_visvalingam_whyatt(0.1f64).epsilon_equals(&corner, &Tolerance::EXACT));
+        assert_eq!(2, noisy.simplify_visvalingam_whyatt(10f64).point_count());
+    }
+
+    #[test]
+    fn cleanup() {
+        let messy = PolyLine::new(vec!(Point3d::ORIGIN,
+                                       Point3d::new(0.0001f64, 0f64, 0f64),
+                                       Point3d::new(1f64, 0f64, 0f64),
+                                       Point3d::new(2f64, 0f64, 0f64),
+                                       Point3d::new(1.5f64, 0f64, 0f64),
+                                       Point3d::new(1.5f64, 1f64, 0f64)));
+
+        assert_eq!(5, messy.remove_duplicates(&Tolerance::DEFAULT).point_count());
+
+        // The doubling back at x = 2 is not collinear in the sense of being redundant
+        let cleaned = messy.remove_collinear(&Tolerance::DEFAULT);
+        assert!(cleaned.epsilon_equals(&PolyLine::new(vec!(Point3d::ORIGIN,
+                                                           Point3d::new(2f64, 0f64, 0f64),
+                                                           Point3d::new(1.5f64, 0f64, 0f64),
+                                                           Point3d::new(1.5f64, 1f64, 0f64))), &Tolerance::EXACT));
+    }
+
+    #[test]
+    fn resampling() {
+        let poly = square();
+        let resampled = poly.resample_by_spacing(0.9f64).unwrap();
+
+        assert_eq!(10, resampled.point_count());
+        assert!(resampled.points()[2].epsilon_equals(&Point3d::new(16f64 / 9f64, 0f64, 0f64), &Tolerance::DEFAULT));
+        assert!(resampled.points()[9] == Point3d::ORIGIN);
+        assert!(poly.resample_by_count(5).unwrap().epsilon_equals(&poly, &Tolerance::DEFAULT));
+        assert!(poly.resample_by_count(1).is_none());
+        assert!(poly.resample_by_spacing(0f64).is_none());
+    }
+
+    #[test]
+    fn chaikin_smoothing() {
+        let open = PolyLine::new(vec!(Point3d::ORIGIN, Point3d::new(4f64, 0f64, 0f64), Point3d::new(4f64, 4f64, 0f64)));
+        let smoothed = open.smooth_chaikin(1);
+        assert_eq!(6, smoothed.point_count());
+        assert!(smoothed.points()[0] == Point3d::ORIGIN);
+        assert!(smoothed.points()[2] == Point3d::new(3f64, 0f64, 0f64));
+
+        let closed = square().smooth_chaikin(2);
+        assert_eq!(17, closed.point_count());
+        assert!(closed.is_closed(&Tolerance::EXACT));
+        assert!(closed.area(&Tolerance::DEFAULT).unwrap() < 4f64);
+    }
+
+    #[test]
+    fn kinks() {
+        let pieces = square().split_at_kinks(std::f64::consts::FRAC_PI_4);
+        assert_eq!(4, pieces.len());
+        assert!(pieces[3].points()[1] == Point3d::ORIGIN);
+
+        assert_eq!(1, square().split_at_kinks(std::f64::consts::PI).len());
     }
 
 }
\ No newline at end of file
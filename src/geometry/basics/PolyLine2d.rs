@@ -0,0 +1,100 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+
+use crate::geometry::basics::BoundingBox2d::BoundingBox2d;
+use crate::geometry::basics::Line2d::Line2d;
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Point2d::Point2d;
+use crate::geometry::basics::PolyLine::PolyLine;
+
+/// A chain of straight segments through a list of points in two-dimensional space
+#[derive(Clone)]
+pub struct PolyLine2d {
+    points:Vec<Point2d>,
+}
+
+impl PolyLine2d {
+
+    pub const UNSET:PolyLine2d = PolyLine2d { points:Vec::new() };
+
+    pub fn new(points:Vec<Point2d>) -> PolyLine2d {
+        PolyLine2d { points }
+    }
+
+    /// Projects a polyline into the 2D coordinates of a plane, discarding each point's height above it
+    pub fn from_plane(polyline:&PolyLine, plane:&Plane) -> PolyLine2d {
+        PolyLine2d::new(polyline.points().iter().map(|point| plane.project(point)).collect())
+    }
+
+    /// Lifts the polyline back onto the plane in 3D. This is the inverse of `from_plane`
+    pub fn to_plane(&self, plane:&Plane) -> PolyLine {
+        PolyLine::new(self.points.iter().map(|point| plane.lift(point)).collect())
+    }
+
+    /// Returns the vertices of the polyline in order
+    pub fn points(&self) -> &[Point2d] {
+        &self.points
+    }
+
+    /// Returns the straight segments between consecutive points
+    pub fn segments(&self) -> impl Iterator<Item = Line2d> + '_ {
+        self.points.windows(2).map(|pair| Line2d::new(pair[0], pair[1]))
+    }
+
+    pub fn length(&self) -> f64 {
+        self.segments().map(|segment| segment.length()).sum()
+    }
+
+    /// Returns the smallest box containing every point
+    pub fn bounding_box(&self) -> BoundingBox2d {
+        BoundingBox2d::from_points(self.points.iter().copied())
+    }
+
+}
+
+impl IsValid for PolyLine2d {
+    fn is_valid(&self) -> bool {
+        !self.points.is_empty() &&
+        self.points.iter().all(|point| point.is_valid())
+    }
+}
+
+impl EpsilonEquals for PolyLine2d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.points.len() == other.points.len() &&
+        self.points.iter().zip(other.points.iter()).all(|(a, b)| a.epsilon_equals(b, tolerance))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::geometry::basics::Point3d::Point3d;
+    use crate::geometry::basics::Vector3d::Vector3d;
+
+    #[test]
+    fn length_and_bounds() {
+        let poly = PolyLine2d::new(vec!(Point2d::ORIGIN,
+                                        Point2d::new(100f64, 0f64),
+                                        Point2d::new(100f64, 500f64)));
+
+        assert_eq!(600f64, poly.length());
+        assert_eq!(50000f64, poly.bounding_box().area());
+        assert_eq!(0f64, PolyLine2d::UNSET.length());
+        assert!(!PolyLine2d::UNSET.is_valid());
+    }
+
+    #[test]
+    fn plane_round_trip() {
+        let plane = Plane::from_point_normal(Point3d::new(0f64, 0f64, 5f64), Vector3d::new(0f64, 1f64, 1f64)).unwrap();
+        let poly = PolyLine::new(vec!(plane.point_at(0f64, 0f64, 0f64),
+                                      plane.point_at(3f64, 0f64, 0f64),
+                                      plane.point_at(3f64, 4f64, 0f64)));
+        let flat = PolyLine2d::from_plane(&poly, &plane);
+
+        assert!(Tolerance::DEFAULT.equals(poly.length(), flat.length()));
+        assert!(flat.to_plane(&plane).epsilon_equals(&poly, &Tolerance::DEFAULT));
+    }
+
+}
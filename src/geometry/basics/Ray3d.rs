@@ -0,0 +1,115 @@
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::Transformable::Transformable;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+use crate::geometry::Transform::{Transform, TransformError};
+
+/// A Ray starting at an origin and continuing forever in one direction
+#[derive(Copy, Clone)]
+pub struct Ray3d {
+    /// The start of the ray
+    pub origin: Point3d,
+    /// The direction of the ray, parameters are measured in multiples of its length
+    pub direction: Vector3d,
+}
+
+impl Ray3d {
+
+    /// An Unset Ray, with an unset origin and direction
+    pub const UNSET:Ray3d = Ray3d { origin:Point3d::UNSET, direction:Vector3d::UNSET };
+
+    /// Constructs a new Ray3d
+    pub fn new(origin:Point3d, direction:Vector3d) -> Ray3d {
+        Ray3d { origin, direction }
+    }
+
+    /// Returns the point at `t` multiples of the direction from the origin
+    pub fn point_at(&self, t:f64) -> Point3d {
+        self.origin + self.direction * t
+    }
+
+    /// Returns the parameter of the closest point on the ray, which is never behind the origin
+    pub fn closest_parameter(&self, point:&Point3d) -> f64 {
+        let length_squared = self.direction.dot(&self.direction);
+        if length_squared == 0f64 {
+            return 0f64;
+        }
+
+        ((*point - self.origin).dot(&self.direction) / length_squared).max(0f64)
+    }
+
+    /// Returns the closest point on the ray to `point`
+    pub fn closest_point(&self, point:&Point3d) -> Point3d {
+        self.point_at(self.closest_parameter(point))
+    }
+
+    /// Returns the shortest distance from `point` to the ray
+    pub fn distance_to(&self, point:&Point3d) -> f64 {
+        self.closest_point(point).distance_to(point)
+    }
+
+}
+
+impl IsValid for Ray3d {
+    fn is_valid(&self) -> bool {
+        self.origin.is_valid() &&
+        self.direction.is_valid() &&
+        self.direction.length() > 0f64
+    }
+
+    fn is_valid_within(&self, tolerance:&Tolerance) -> bool {
+        self.is_valid() && !self.direction.is_tiny(tolerance)
+    }
+}
+
+impl Transformable for Ray3d {
+    /// Transforms the origin and a point along the direction, so perspective transforms are handled too
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        let mut origin = self.origin;
+        let mut ahead = self.origin + self.direction;
+        origin.transform(xform)?;
+        ahead.transform(xform)?;
+
+        self.origin = origin;
+        self.direction = ahead - origin;
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Ray3d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        self.origin.epsilon_equals(&other.origin, tolerance) &&
+        self.direction.epsilon_equals(&other.direction, tolerance)
+    }
+}
+
+impl PartialEq for Ray3d {
+    fn eq(&self, other: &Self) -> bool {
+        self.origin.eq(&other.origin) &&
+        self.direction.eq(&other.direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn is_valid() {
+        assert!(!Ray3d::UNSET.is_valid());
+        assert!(!Ray3d::new(Point3d::ORIGIN, Vector3d::ORIGIN).is_valid());
+        assert!(Ray3d::new(Point3d::ORIGIN, Vector3d::XAXIS).is_valid());
+    }
+
+    #[test]
+    pub fn closest_point_behind_origin() {
+        let ray = Ray3d::new(Point3d::ORIGIN, Vector3d::new(2f64, 0f64, 0f64));
+
+        assert!(ray.closest_point(&Point3d::new(-5f64, 3f64, 0f64)) == Point3d::ORIGIN);
+        assert_eq!(2f64, ray.closest_parameter(&Point3d::new(4f64, 3f64, 0f64)));
+        assert_eq!(3f64, ray.distance_to(&Point3d::new(4f64, 3f64, 0f64)));
+    }
+
+}
@@ -0,0 +1,191 @@
+use std::ops;
+
+use crate::EpsilonEquals::EpsilonEquals;
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::Point2d::Point2d;
+
+/// A direction and magnitude in two-dimensional space
+#[derive(Copy, Clone)]
+pub struct Vector2d
+{
+    /// The X component
+    pub x: f64,
+    /// The Y component
+    pub y: f64,
+}
+
+impl Vector2d {
+
+    // Constants
+
+    /// The zero length vector, (0,0)
+    pub const ORIGIN:Vector2d = Vector2d { x:0f64, y:0f64 };
+
+    /// An Unset vector. A completely invalid vector that does not exist
+    pub const UNSET:Vector2d = Vector2d { x:f64::NAN, y:f64::NAN };
+
+    /// The unit X axis
+    pub const XAXIS:Vector2d = Vector2d { x:1f64, y:0f64 };
+
+    /// The unit Y axis
+    pub const YAXIS:Vector2d = Vector2d { x:0f64, y:1f64 };
+
+    // Constructors
+
+    /// Constructs a new Vector2d
+    pub fn new(x:f64, y:f64) -> Vector2d {
+        Vector2d { x, y }
+    }
+
+    // Methods
+
+    /// Returns the length of the vector
+    pub fn length(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// Returns a vector of length one pointing the same way
+    pub fn unitize(vec:&Vector2d) -> Vector2d {
+        *vec / vec.length()
+    }
+
+    /// Returns the dot product of the two vectors
+    pub fn dot(&self, other:&Vector2d) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the Z component of the cross product of the two vectors lifted into 3D.
+    /// It is positive when `other` is anticlockwise from this vector
+    pub fn cross(&self, other:&Vector2d) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Returns the unsigned angle between the two vectors in radians, from 0 to PI
+    pub fn angle_to(&self, other:&Vector2d) -> f64 {
+        self.cross(other).abs().atan2(self.dot(other))
+    }
+
+    /// Returns the signed angle from this vector to the other, positive anticlockwise, from -PI to PI
+    pub fn signed_angle_to(&self, other:&Vector2d) -> f64 {
+        self.cross(other).atan2(self.dot(other))
+    }
+
+    /// Returns the vector turned a quarter turn anticlockwise
+    pub fn perpendicular(&self) -> Vector2d {
+        Vector2d::new(-self.y, self.x)
+    }
+
+    /// Returns the vector turned anticlockwise by `angle` radians
+    pub fn rotate(&self, angle:f64) -> Vector2d {
+        let (sin, cos) = angle.sin_cos();
+        Vector2d::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Returns true if the vector has a length of one within tolerance
+    pub fn is_unit(&self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.length(), 1f64)
+    }
+
+    /// Returns true if the vector is too short to have a meaningful direction
+    pub fn is_tiny(&self, tolerance:&Tolerance) -> bool {
+        tolerance.is_zero(self.length())
+    }
+
+}
+
+impl ops::Add<Vector2d> for Vector2d {
+    type Output = Vector2d;
+    fn add(self, other: Vector2d) -> Self::Output {
+        Vector2d::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl ops::Sub<Vector2d> for Vector2d {
+    type Output = Vector2d;
+    fn sub(self, other: Vector2d) -> Self::Output {
+        Vector2d::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl ops::Mul<f64> for Vector2d {
+    type Output = Vector2d;
+    fn mul(self, factor: f64) -> Self::Output {
+        Vector2d::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl ops::Mul<Vector2d> for f64 {
+    type Output = Vector2d;
+    fn mul(self, vector: Vector2d) -> Self::Output {
+        vector * self
+    }
+}
+
+impl ops::Div<f64> for Vector2d {
+    type Output = Vector2d;
+    fn div(self, factor: f64) -> Self::Output {
+        Vector2d::new(self.x / factor, self.y / factor)
+    }
+}
+
+impl ops::Neg for Vector2d {
+    type Output = Vector2d;
+    fn neg(self) -> Self::Output {
+        Vector2d::new(-self.x, -self.y)
+    }
+}
+
+impl From<Point2d> for Vector2d {
+    fn from(point: Point2d) -> Self {
+        Vector2d::new(point.x, point.y)
+    }
+}
+
+impl IsValid for Vector2d {
+    fn is_valid(&self) -> bool {
+        self.x.is_finite() &&
+        self.y.is_finite()
+    }
+}
+
+impl EpsilonEquals for Vector2d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.x, other.x) &&
+        tolerance.equals(self.y, other.y)
+    }
+}
+
+impl PartialEq for Vector2d {
+    fn eq(&self, other: &Self) -> bool {
+        self.x.eq(&other.x) &&
+        self.y.eq(&other.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    pub fn is_valid() {
+        assert!(Vector2d::XAXIS.is_valid());
+        assert!(!Vector2d::UNSET.is_valid());
+    }
+
+    #[test]
+    pub fn products_and_angles() {
+        let a = Vector2d::new(3f64, 4f64);
+
+        assert_eq!(5f64, a.length());
+        assert_eq!(11f64, a.dot(&Vector2d::new(1f64, 2f64)));
+        assert_eq!(1f64, Vector2d::XAXIS.cross(&Vector2d::YAXIS));
+        assert!(Tolerance::DEFAULT.angle_equals(-FRAC_PI_2, Vector2d::YAXIS.signed_angle_to(&Vector2d::XAXIS)));
+        assert!(Tolerance::DEFAULT.angle_equals(FRAC_PI_2, Vector2d::YAXIS.angle_to(&Vector2d::XAXIS)));
+        assert!(a.perpendicular() == Vector2d::new(-4f64, 3f64));
+        assert!(Vector2d::XAXIS.rotate(FRAC_PI_2).epsilon_equals(&Vector2d::YAXIS, &Tolerance::DEFAULT));
+        assert!(Vector2d::unitize(&a).is_unit(&Tolerance::DEFAULT));
+    }
+
+}
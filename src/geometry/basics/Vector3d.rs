@@ -1,16 +1,22 @@
 use std::ops;
 
+use crate::EpsilonEquals::EpsilonEquals;
 use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::Transformable::Transformable;
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::Transform::{Transform, TransformError};
 
 /// A Point in three-dimensional space
+#[derive(Copy, Clone)]
 pub struct Vector3d
 {
     /// The X coordinate
-    pub x: f32,
+    pub x: f64,
     /// The Y coordinate
-    pub y: f32,
+    pub y: f64,
     /// The Z coordinate
-    pub z: f32,
+    pub z: f64,
 }
 
 impl Vector3d {
@@ -18,42 +24,42 @@ impl Vector3d {
     // Constants
     
     /// The Origin, (0,0,0)
-    pub const ORIGIN:Vector3d = Vector3d {x:0f32, y:0f32, z:0f32 };
+    pub const ORIGIN:Vector3d = Vector3d {x:0f64, y:0f64, z:0f64 };
 
     /// An Unset point. A completely invalid point that does not exist
-    pub const UNSET:Vector3d = Vector3d { x:f32::NAN, y:f32::NAN, z:f32::NAN };
+    pub const UNSET:Vector3d = Vector3d { x:f64::NAN, y:f64::NAN, z:f64::NAN };
 
     /// A Point at the edge of positive Infinity
-    pub const INFINITY:Vector3d = Vector3d { x:f32::INFINITY, y:f32::INFINITY, z:f32::INFINITY };
+    pub const INFINITY:Vector3d = Vector3d { x:f64::INFINITY, y:f64::INFINITY, z:f64::INFINITY };
 
     /// A point at the edge of negative infinity
-    pub const NEGATIVE_INFINITY:Vector3d = Vector3d { x:f32::NEG_INFINITY, y:f32::NEG_INFINITY, z:f32::NEG_INFINITY };
+    pub const NEGATIVE_INFINITY:Vector3d = Vector3d { x:f64::NEG_INFINITY, y:f64::NEG_INFINITY, z:f64::NEG_INFINITY };
 
     /// The Maximum possible Point
-    pub const MAX:Vector3d = Vector3d { x:f32::MAX, y:f32::MAX, z:f32::MAX };
+    pub const MAX:Vector3d = Vector3d { x:f64::MAX, y:f64::MAX, z:f64::MAX };
 
     /// The Minimum possible Point
-    pub const MIN:Vector3d = Vector3d { x:f32::MIN, y:f32::MIN, z:f32::MIN };
+    pub const MIN:Vector3d = Vector3d { x:f64::MIN, y:f64::MIN, z:f64::MIN };
 
     /// A Vector along the X Axis (1,0,0)
-    pub const XAXIS:Vector3d = Vector3d { x:1f32, y:0f32, z:0f32 };
+    pub const XAXIS:Vector3d = Vector3d { x:1f64, y:0f64, z:0f64 };
     
     /// A Vector along the Y Axis (1,0,0)
-    pub const YAXIS:Vector3d = Vector3d { x:0f32, y:1f32, z:0f32 };
+    pub const YAXIS:Vector3d = Vector3d { x:0f64, y:1f64, z:0f64 };
     
     /// A Vector along the Z Axis (1,0,0)
-    pub const ZAXIS:Vector3d = Vector3d { x:0f32, y:0f32, z:1f32 };
+    pub const ZAXIS:Vector3d = Vector3d { x:0f64, y:0f64, z:1f64 };
 
     // Constructors
 
     /// Constructs a new Vector3d
-    pub fn new(x:f32, y:f32, z:f32) -> Vector3d {
+    pub fn new(x:f64, y:f64, z:f64) -> Vector3d {
         Vector3d { x, y, z }
     }
 
     /// Returns the length of the Vector3d
-    pub fn length(&self) -> f32 {
-        let pows = self.x.powf(2f32) + self.y.powf(2f32) + self.z.powf(2f32);
+    pub fn length(&self) -> f64 {
+        let pows = self.x.powf(2f64) + self.y.powf(2f64) + self.z.powf(2f64);
         pows.sqrt()
     }
 
@@ -86,7 +92,7 @@ impl Vector3d {
     }
 
     /// Multiplies a point by a factor
-    pub fn multiply_by_factor(p1: &Vector3d, factor:f32) -> Vector3d {
+    pub fn multiply_by_factor(p1: &Vector3d, factor:f64) -> Vector3d {
         Vector3d::new(p1.x * factor,
             p1.y * factor,
             p1.z * factor)
@@ -100,12 +106,95 @@ impl Vector3d {
     }
 
     /// Divides a point by a factor
-    pub fn divide_by_factor(p1: &Vector3d, factor:f32) -> Vector3d {
+    pub fn divide_by_factor(p1: &Vector3d, factor:f64) -> Vector3d {
         Vector3d::new(p1.x / factor,
             p1.y / factor,
             p1.z / factor)
     }
 
+    /// Returns the dot product of the two vectors
+    pub fn dot(&self, other:&Vector3d) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Returns the cross product of the two vectors, perpendicular to both following the right-hand rule
+    /// https://en.wikipedia.org/wiki/Cross_product
+    pub fn cross(&self, other:&Vector3d) -> Vector3d {
+        Vector3d::new(self.y * other.z - self.z * other.y,
+                    self.z * other.x - self.x * other.z,
+                    self.x * other.y - self.y * other.x)
+    }
+
+    /// Returns the unsigned angle between the two vectors in radians, from 0 to PI
+    pub fn angle_to(&self, other:&Vector3d) -> f64 {
+        // atan2 stays accurate for nearly parallel vectors, where acos of the dot product does not
+        self.cross(other).length().atan2(self.dot(other))
+    }
+
+    /// Returns the signed angle from this vector to the other, measured in `plane` about its Z axis.
+    /// Both vectors are projected into the plane first, and the result ranges from -PI to PI
+    pub fn angle_to_in_plane(&self, other:&Vector3d, plane:&Plane) -> f64 {
+        let a = self.reject_from(&plane.z);
+        let b = other.reject_from(&plane.z);
+
+        a.cross(&b).dot(&plane.z).atan2(a.dot(&b) * plane.z.length())
+    }
+
+    /// Returns the component of this vector that lies along `onto`
+    pub fn project_onto(&self, onto:&Vector3d) -> Vector3d {
+        *onto * (self.dot(onto) / onto.dot(onto))
+    }
+
+    /// Returns the component of this vector that is perpendicular to `from`
+    pub fn reject_from(&self, from:&Vector3d) -> Vector3d {
+        *self - self.project_onto(from)
+    }
+
+    /// Returns a vector of the same length that is perpendicular to this one
+    pub fn perpendicular_to(&self) -> Vector3d {
+        // Crossing with the axis this vector is furthest from gives the best conditioned result
+        let (x, y, z) = (self.x.abs(), self.y.abs(), self.z.abs());
+        let axis = if x <= y && x <= z {
+            Vector3d::XAXIS
+        } else if y <= z {
+            Vector3d::YAXIS
+        } else {
+            Vector3d::ZAXIS
+        };
+
+        let perpendicular = self.cross(&axis);
+        perpendicular * (self.length() / perpendicular.length())
+    }
+
+    /// Returns true if the vectors point along the same line, in either direction, within the angle tolerance
+    pub fn is_parallel_to(&self, other:&Vector3d, tolerance:&Tolerance) -> bool {
+        if self.is_tiny(tolerance) || other.is_tiny(tolerance) {
+            return false;
+        }
+
+        let angle = self.angle_to(other);
+        angle <= tolerance.angle || angle >= std::f64::consts::PI - tolerance.angle
+    }
+
+    /// Returns true if the vectors are at right angles within the angle tolerance
+    pub fn is_perpendicular_to(&self, other:&Vector3d, tolerance:&Tolerance) -> bool {
+        if self.is_tiny(tolerance) || other.is_tiny(tolerance) {
+            return false;
+        }
+
+        tolerance.angle_equals(self.angle_to(other), std::f64::consts::FRAC_PI_2)
+    }
+
+    /// Returns true if the vector has a length of one within tolerance
+    pub fn is_unit(&self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.length(), 1f64)
+    }
+
+    /// Returns true if the vector is too short to have a meaningful direction
+    pub fn is_tiny(&self, tolerance:&Tolerance) -> bool {
+        tolerance.is_zero(self.length())
+    }
+
 }
 
 
@@ -130,6 +219,34 @@ impl ops::Mul<Vector3d> for Vector3d {
     }
 }
 
+impl ops::Mul<f64> for Vector3d {
+    type Output = Vector3d;
+    fn mul(self, factor: f64) -> Self::Output {
+        Vector3d::multiply_by_factor(&self, factor)
+    }
+}
+
+impl ops::Mul<Vector3d> for f64 {
+    type Output = Vector3d;
+    fn mul(self, vector: Vector3d) -> Self::Output {
+        Vector3d::multiply_by_factor(&vector, self)
+    }
+}
+
+impl ops::Div<f64> for Vector3d {
+    type Output = Vector3d;
+    fn div(self, factor: f64) -> Self::Output {
+        Vector3d::divide_by_factor(&self, factor)
+    }
+}
+
+impl ops::Neg for Vector3d {
+    type Output = Vector3d;
+    fn neg(self) -> Self::Output {
+        Vector3d::new(-self.x, -self.y, -self.z)
+    }
+}
+
 impl ops::Div<Vector3d> for Vector3d {
     type Output = Vector3d;
     fn div(self, rhs: Vector3d) -> Self::Output {
@@ -145,9 +262,29 @@ impl  IsValid for Vector3d {
     }
 }
 
-impl Clone for Vector3d {
-    fn clone(&self) -> Vector3d {
-        Vector3d::new(self.x, self.y, self.z)
+impl Transformable for Vector3d {
+    /// Transforms the vector by the linear part of the transform.
+    /// Vectors have no position, so translations and perspective terms are ignored
+    fn transform(&mut self, xform:&Transform) -> Result<(), TransformError> {
+        if !xform.is_valid() {
+            return Err(TransformError::Invalid);
+        }
+
+        let m = &xform.m;
+        let (x, y, z) = (self.x, self.y, self.z);
+
+        self.x = m[0][0] * x + m[0][1] * y + m[0][2] * z;
+        self.y = m[1][0] * x + m[1][1] * y + m[1][2] * z;
+        self.z = m[2][0] * x + m[2][1] * y + m[2][2] * z;
+        Ok(())
+    }
+}
+
+impl EpsilonEquals for Vector3d {
+    fn epsilon_equals(&self, other:&Self, tolerance:&Tolerance) -> bool {
+        tolerance.equals(self.x, other.x) &&
+        tolerance.equals(self.y, other.y) &&
+        tolerance.equals(self.z, other.z)
     }
 }
 
@@ -157,15 +294,13 @@ impl PartialEq for Vector3d {
         self.y == other.y &&
         self.z == other.z
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geometry::basics::Point3d::Point3d;
+    use std::f64::consts::FRAC_PI_4;
 
     #[test]
     pub fn is_valid() {
@@ -183,73 +318,141 @@ mod tests {
     #[test]
     pub fn vector_origin() {
         let vector = Vector3d::ORIGIN;
-        assert_eq!(0f32, vector.x);
-        assert_eq!(0f32, vector.y);
-        assert_eq!(0f32, vector.z);
+        assert_eq!(0f64, vector.x);
+        assert_eq!(0f64, vector.y);
+        assert_eq!(0f64, vector.z);
     }
 
     #[test]
     pub fn add_two_vectors() {
-        let vector: Vector3d = Vector3d::new(100f32, 100f32, 100f32);
-        let vector2: Vector3d = Vector3d::new(200f32, 200f32, 200f32);
+        let vector: Vector3d = Vector3d::new(100f64, 100f64, 100f64);
+        let vector2: Vector3d = Vector3d::new(200f64, 200f64, 200f64);
 
         let new_vector = vector + vector2;
-        assert_eq!(300f32, new_vector.x);
-        assert_eq!(300f32, new_vector.y);
-        assert_eq!(300f32, new_vector.z);
+        assert_eq!(300f64, new_vector.x);
+        assert_eq!(300f64, new_vector.y);
+        assert_eq!(300f64, new_vector.z);
     }
 
     #[test]
     pub fn subtract_two_vectors() {
-        let vector: Vector3d = Vector3d::new(100f32, 100f32, 100f32);
-        let vector2: Vector3d = Vector3d::new(200f32, 200f32, 200f32);
+        let vector: Vector3d = Vector3d::new(100f64, 100f64, 100f64);
+        let vector2: Vector3d = Vector3d::new(200f64, 200f64, 200f64);
 
         let new_vector = vector2 - vector;
-        assert_eq!(100f32, new_vector.x);
-        assert_eq!(100f32, new_vector.y);
-        assert_eq!(100f32, new_vector.z);
+        assert_eq!(100f64, new_vector.x);
+        assert_eq!(100f64, new_vector.y);
+        assert_eq!(100f64, new_vector.z);
     }
 
     #[test]
     pub fn divide_two_vectors() {
-        let vector: Vector3d = Vector3d::new(100f32, 100f32, 100f32);
-        let vector2: Vector3d = Vector3d::new(200f32, 200f32, 200f32);
+        let vector: Vector3d = Vector3d::new(100f64, 100f64, 100f64);
+        let vector2: Vector3d = Vector3d::new(200f64, 200f64, 200f64);
 
         let new_vector = vector2 / vector;
-        assert_eq!(2f32, new_vector.x);
-        assert_eq!(2f32, new_vector.y);
-        assert_eq!(2f32, new_vector.z);
+        assert_eq!(2f64, new_vector.x);
+        assert_eq!(2f64, new_vector.y);
+        assert_eq!(2f64, new_vector.z);
     }
 
     #[test]
     pub fn divide_by_factor() {
-        let vector = Vector3d::new(100f32, 200f32, 300f32);
-        let new_vector = Vector3d::divide_by_factor(&vector, 2f32);
+        let vector = Vector3d::new(100f64, 200f64, 300f64);
+        let new_vector = Vector3d::divide_by_factor(&vector, 2f64);
         
-        assert_eq!(50f32, new_vector.x);
-        assert_eq!(100f32, new_vector.y);
-        assert_eq!(150f32, new_vector.z);
+        assert_eq!(50f64, new_vector.x);
+        assert_eq!(100f64, new_vector.y);
+        assert_eq!(150f64, new_vector.z);
     }
 
     #[test]
     pub fn multiply_two_vectors() {
-        let vector: Vector3d = Vector3d::new(4f32, 4f32, 4f32);
-        let vector2: Vector3d = Vector3d::new(5f32, 5f32, 5f32);
+        let vector: Vector3d = Vector3d::new(4f64, 4f64, 4f64);
+        let vector2: Vector3d = Vector3d::new(5f64, 5f64, 5f64);
 
         let new_vector = vector * vector2;
-        assert_eq!(20f32, new_vector.x);
-        assert_eq!(20f32, new_vector.y);
-        assert_eq!(20f32, new_vector.z);
+        assert_eq!(20f64, new_vector.x);
+        assert_eq!(20f64, new_vector.y);
+        assert_eq!(20f64, new_vector.z);
     }
 
     #[test]
     pub fn multiply_by_factor() {
-        let vector: Vector3d = Vector3d::new(100f32, 200f32, 300f32);
-        let bigger_vector = Vector3d::multiply_by_factor(&vector, 2f32);
+        let vector: Vector3d = Vector3d::new(100f64, 200f64, 300f64);
+        let bigger_vector = Vector3d::multiply_by_factor(&vector, 2f64);
         
-        assert_eq!(200f32, bigger_vector.x);
-        assert_eq!(400f32, bigger_vector.y);
-        assert_eq!(600f32, bigger_vector.z);
+        assert_eq!(200f64, bigger_vector.x);
+        assert_eq!(400f64, bigger_vector.y);
+        assert_eq!(600f64, bigger_vector.z);
+    }
+
+    #[test]
+    pub fn translate_is_ignored() {
+        let mut vector = Vector3d::new(1f64, 2f64, 3f64);
+        vector.translate(&Vector3d::new(10f64, 10f64, 10f64)).unwrap();
+
+        assert_eq!(1f64, vector.x);
+        assert_eq!(2f64, vector.y);
+        assert_eq!(3f64, vector.z);
+
+        vector.scale(&Point3d::new(5f64, 5f64, 5f64), 2f64).unwrap();
+        assert_eq!(2f64, vector.x);
+        assert_eq!(4f64, vector.y);
+        assert_eq!(6f64, vector.z);
+    }
+
+    #[test]
+    pub fn dot_and_cross() {
+        let x = Vector3d::XAXIS;
+        let y = Vector3d::YAXIS;
+
+        assert_eq!(0f64, x.dot(&y));
+        assert_eq!(32f64, Vector3d::new(1f64, 2f64, 3f64).dot(&Vector3d::new(4f64, 5f64, 6f64)));
+        assert!(x.cross(&y) == Vector3d::ZAXIS);
+        assert!(y.cross(&x) == -Vector3d::ZAXIS);
+    }
+
+    #[test]
+    pub fn angles() {
+        let tolerance = Tolerance::DEFAULT;
+        let diagonal = Vector3d::new(1f64, 1f64, 0f64);
+
+        assert!(tolerance.angle_equals(FRAC_PI_4, Vector3d::XAXIS.angle_to(&diagonal)));
+        assert!(tolerance.angle_equals(-FRAC_PI_4, diagonal.angle_to_in_plane(&Vector3d::XAXIS, &Plane::WORLDXY)));
+        assert!(tolerance.angle_equals(FRAC_PI_4, Vector3d::XAXIS.angle_to_in_plane(&diagonal, &Plane::WORLDXY)));
+    }
+
+    #[test]
+    pub fn projection() {
+        let vector = Vector3d::new(3f64, 4f64, 5f64);
+        let along = vector.project_onto(&(Vector3d::XAXIS * 2f64));
+        let across = vector.reject_from(&Vector3d::XAXIS);
+
+        assert!(along == Vector3d::new(3f64, 0f64, 0f64));
+        assert!(across == Vector3d::new(0f64, 4f64, 5f64));
+    }
+
+    #[test]
+    pub fn parallel_and_perpendicular() {
+        let tolerance = Tolerance::DEFAULT;
+        let vector = Vector3d::new(1f64, 2f64, 3f64);
+        let perpendicular = vector.perpendicular_to();
+
+        assert!(vector.is_perpendicular_to(&perpendicular, &tolerance));
+        assert!(tolerance.equals(vector.length(), perpendicular.length()));
+        assert!(vector.is_parallel_to(&(vector * -2f64), &tolerance));
+        assert!(!vector.is_parallel_to(&perpendicular, &tolerance));
+        assert!(!vector.is_parallel_to(&Vector3d::ORIGIN, &tolerance));
+    }
+
+    #[test]
+    pub fn unit_and_tiny() {
+        let tolerance = Tolerance::DEFAULT;
+
+        assert!(Vector3d::unitize(&Vector3d::new(1f64, 2f64, 3f64)).is_unit(&tolerance));
+        assert!(Vector3d::new(0.0001f64, 0f64, 0f64).is_tiny(&tolerance));
+        assert!(!Vector3d::XAXIS.is_tiny(&tolerance));
     }
 
 }
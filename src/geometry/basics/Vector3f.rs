@@ -0,0 +1,94 @@
+use crate::IsValid::IsValid;
+use crate::geometry::basics::Vector3d::Vector3d;
+
+/// A single precision Vector in three-dimensional space.
+/// Intended for handing geometry to the GPU, all modelling should be done with a Vector3d
+#[derive(Copy, Clone)]
+pub struct Vector3f
+{
+    /// The X coordinate
+    pub x: f32,
+    /// The Y coordinate
+    pub y: f32,
+    /// The Z coordinate
+    pub z: f32,
+}
+
+impl Vector3f {
+
+    // Constants
+
+    /// The Origin, (0,0,0)
+    pub const ORIGIN:Vector3f = Vector3f { x:0f32, y:0f32, z:0f32 };
+
+    /// An Unset vector. A completely invalid vector that does not exist
+    pub const UNSET:Vector3f = Vector3f { x:f32::NAN, y:f32::NAN, z:f32::NAN };
+
+    // Constructors
+
+    /// Constructs a new Vector3f
+    pub fn new(x:f32, y:f32, z:f32) -> Vector3f {
+        Vector3f { x, y, z }
+    }
+
+}
+
+impl From<Vector3d> for Vector3f {
+    /// Narrows a double precision vector, rounding each coordinate to the nearest f32
+    fn from(vector: Vector3d) -> Self {
+        Vector3f::new(vector.x as f32, vector.y as f32, vector.z as f32)
+    }
+}
+
+impl From<Vector3f> for Vector3d {
+    /// Widens a single precision vector, this is lossless
+    fn from(vector: Vector3f) -> Self {
+        Vector3d::new(vector.x as f64, vector.y as f64, vector.z as f64)
+    }
+}
+
+impl IsValid for Vector3f {
+    fn is_valid(&self) -> bool {
+        self.x.is_finite() &&
+        self.y.is_finite() &&
+        self.z.is_finite()
+    }
+}
+
+impl PartialEq for Vector3f {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x &&
+        self.y == other.y &&
+        self.z == other.z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn is_valid() {
+        assert!(!Vector3f::UNSET.is_valid());
+        assert!(Vector3f::ORIGIN.is_valid());
+    }
+
+    #[test]
+    pub fn round_trip() {
+        let single = Vector3f::new(1.5f32, -2.25f32, 1000f32);
+        let double = Vector3d::from(single);
+        assert_eq!(1.5f64, double.x);
+        assert_eq!(-2.25f64, double.y);
+        assert_eq!(1000f64, double.z);
+
+        assert!(Vector3f::from(double) == single);
+    }
+
+    #[test]
+    pub fn narrowing_rounds() {
+        let precise = Vector3d::new(1_000_000.001f64, 0f64, 0f64);
+        let single = Vector3f::from(precise);
+        assert_eq!(1_000_000f32, single.x);
+    }
+
+}
@@ -1,9 +1,26 @@
+pub mod Point2d;
 pub mod Point3d;
+pub mod Point3f;
+pub mod Point4d;
+pub mod Vector2d;
 pub mod Vector3d;
+pub mod Vector3f;
+pub mod Line2d;
 pub mod Line;
+pub mod Ray3d;
+pub mod InfiniteLine;
 pub mod Circle;
+pub mod Arc;
+pub mod Ellipse;
+pub mod NurbsCurve;
+pub mod PolyLine2d;
 pub mod PolyLine;
 pub mod Box;
+pub mod Mesh;
+pub mod BoundingBox2d;
 pub mod BoundingBox;
 pub mod Plane;
-pub mod Interval;
\ No newline at end of file
+pub mod Interval;
+pub mod IntervalScalar;
+pub mod IntervalPoint3d;
+pub mod IntervalSet;
\ No newline at end of file
@@ -0,0 +1,284 @@
+use crate::Tolerance::Tolerance;
+use crate::geometry::arrangement::{Arrangement, Edge};
+use crate::geometry::basics::BoundingBox2d::BoundingBox2d;
+use crate::geometry::basics::Line2d::Line2d;
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Point2d::Point2d;
+use crate::geometry::basics::PolyLine::PolyLine;
+
+// A region is given as a list of closed loops, and is the area inside an odd number of them.
+// That way islands and holes can be listed in any order and wind either way.
+// The loops of both regions are cut against each other, and every piece with the result on its left and not on its right
+// is kept. Each edge goes in both ways round, so the piece facing the right way is always there to keep,
+// and pieces from both regions lying on top of each other are only kept once.
+
+/// Which parts of two regions a boolean operation keeps
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Operation {
+    /// Everything inside either region
+    Union,
+    /// Everything inside both regions
+    Intersection,
+    /// Everything inside the first region but not the second
+    Difference,
+    /// Everything inside exactly one of the regions
+    Xor,
+}
+
+impl Operation {
+
+    /// Returns true if a point inside or outside each region is in the result
+    pub fn keeps(&self, in_a:bool, in_b:bool) -> bool {
+        match self {
+            Operation::Union => in_a || in_b,
+            Operation::Intersection => in_a && in_b,
+            Operation::Difference => in_a && !in_b,
+            Operation::Xor => in_a != in_b,
+        }
+    }
+
+}
+
+/// Returns the union of two regions lying in a plane. See `boolean`
+pub fn union(a:&[PolyLine], b:&[PolyLine], plane:&Plane, tolerance:&Tolerance) -> Vec<PolyLine> {
+    boolean(a, b, Operation::Union, plane, tolerance)
+}
+
+/// Returns the intersection of two regions lying in a plane. See `boolean`
+pub fn intersection(a:&[PolyLine], b:&[PolyLine], plane:&Plane, tolerance:&Tolerance) -> Vec<PolyLine> {
+    boolean(a, b, Operation::Intersection, plane, tolerance)
+}
+
+/// Returns the first region with the second taken away. See `boolean`
+pub fn difference(a:&[PolyLine], b:&[PolyLine], plane:&Plane, tolerance:&Tolerance) -> Vec<PolyLine> {
+    boolean(a, b, Operation::Difference, plane, tolerance)
+}
+
+/// Returns the parts of two regions that do not overlap. See `boolean`
+pub fn xor(a:&[PolyLine], b:&[PolyLine], plane:&Plane, tolerance:&Tolerance) -> Vec<PolyLine> {
+    boolean(a, b, Operation::Xor, plane, tolerance)
+}
+
+/// Combines two regions lying in a plane, each given as closed loops for its islands and holes.
+///
+/// A region is everything inside an odd number of its loops, so holes may wind either way and loops may be in any order.
+/// Polylines that are not closed are ignored, and points off the plane are projected onto it first.
+/// The result comes out as closed loops, anticlockwise around islands and clockwise around holes looking down the plane's Z axis.
+/// Edges and vertices of the two regions closer than the tolerance are treated as shared, and even for an exact tolerance
+/// anything within a billionth of the size of the regions is. Points left in the middle of straight runs are removed
+pub fn boolean(a:&[PolyLine], b:&[PolyLine], operation:Operation, plane:&Plane, tolerance:&Tolerance) -> Vec<PolyLine> {
+    let mut edges = Vec::new();
+    for (region, loops) in [(Region::A, a), (Region::B, b)] {
+        for polyline in loops.iter().filter(|polyline| polyline.is_closed(tolerance)) {
+            let points:Vec<Point2d> = polyline.points().iter().map(|point| plane.project(point)).collect();
+            for pair in points.windows(2) {
+                edges.push(Edge { start:pair[0], end:pair[1], tag:(region, true) });
+                edges.push(Edge { start:pair[1], end:pair[0], tag:(region, false) });
+            }
+        }
+    }
+
+    // Cutting and sampling either side of each piece needs some room, even for an exact tolerance
+    let size = BoundingBox2d::from_points(edges.iter().map(|edge| edge.start)).diagonal().length();
+    if size == 0f64 {
+        return Vec::new();
+    }
+    let slack = tolerance.absolute.max(size * 1e-9f64);
+
+    let arrangement = Arrangement::new(&edges, slack);
+    let inside = |point:Point2d, region:Region| arrangement.crossings(point, |tag| *tag == (region, true)) % 2 == 1;
+    let keep = arrangement.boundary(|point| operation.keeps(inside(point, Region::A), inside(point, Region::B)));
+
+    arrangement.stitch(&keep).into_iter()
+        .filter(|points| points.len() > 3 && points[0] == points[points.len() - 1])
+        .filter_map(|points| tidy(points, slack))
+        .map(|points| PolyLine::new(points.iter().map(|point| plane.lift(point)).collect()))
+        .collect()
+}
+
+/// Which of the two regions an edge came from
+#[derive(Copy, Clone, PartialEq)]
+enum Region {
+    A,
+    B,
+}
+
+/// Removes points from a closed loop wherever the straight line skipping them stays within tolerance of every point
+/// it skips, including where the loop starts. Returns None if less than a triangle is left
+fn tidy(mut points:Vec<Point2d>, tolerance:f64) -> Option<Vec<Point2d>> {
+    points.pop();
+    if points.len() < 3 {
+        return None;
+    }
+
+    // The point furthest from any other is a corner of the loop's hull, so it is always kept and can safely go first
+    let first = (0..points.len())
+        .max_by(|a, b| points[*a].distance_to(&points[0]).total_cmp(&points[*b].distance_to(&points[0])))
+        .unwrap_or(0);
+    points.rotate_left(first);
+
+    let count = points.len();
+    let mut kept = vec![points[0]];
+    let mut skipped:Vec<Point2d> = Vec::new();
+    for index in 1..count {
+        let (point, next) = (points[index], points[(index + 1) % count]);
+        let chord = Line2d::new(kept[kept.len() - 1], next);
+        if chord.distance_to(&point) <= tolerance && skipped.iter().all(|other| chord.distance_to(other) <= tolerance) {
+            skipped.push(point);
+        } else {
+            kept.push(point);
+            skipped.clear();
+        }
+    }
+
+    if kept.len() < 3 {
+        return None;
+    }
+
+    kept.push(kept[0]);
+    Some(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::basics::Point3d::Point3d;
+    use crate::geometry::basics::PolyLine::Orientation;
+    use crate::geometry::basics::Vector3d::Vector3d;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
+
+    fn rectangle(plane:&Plane, min:(f64, f64), max:(f64, f64)) -> PolyLine {
+        PolyLine::new(vec![plane.point_at(min.0, min.1, 0f64), plane.point_at(max.0, min.1, 0f64),
+                           plane.point_at(max.0, max.1, 0f64), plane.point_at(min.0, max.1, 0f64),
+                           plane.point_at(min.0, min.1, 0f64)])
+    }
+
+    /// Returns the area of islands less the area of holes
+    fn area(loops:&[PolyLine], plane:&Plane) -> f64 {
+        loops.iter()
+            .map(|polyline| match polyline.orientation(plane, &TOLERANCE) {
+                Some(Orientation::Anticlockwise) => polyline.area(&TOLERANCE).unwrap(),
+                _ => -polyline.area(&TOLERANCE).unwrap(),
+            })
+            .sum()
+    }
+
+    #[test]
+    pub fn overlapping_squares() {
+        let plane = Plane::WORLDXY;
+        let a = [rectangle(&plane, (0f64, 0f64), (2f64, 2f64))];
+        let b = [rectangle(&plane, (1f64, 1f64), (3f64, 3f64))];
+
+        let united = union(&a, &b, &plane, &TOLERANCE);
+        assert_eq!(1, united.len());
+        assert_eq!(9, united[0].point_count());
+        assert!(TOLERANCE.equals(7f64, area(&united, &plane)));
+
+        let common = intersection(&a, &b, &plane, &TOLERANCE);
+        assert_eq!(1, common.len());
+        assert!(TOLERANCE.equals(1f64, area(&common, &plane)));
+
+        assert!(TOLERANCE.equals(3f64, area(&difference(&a, &b, &plane, &TOLERANCE), &plane)));
+
+        let apart = xor(&a, &b, &plane, &TOLERANCE);
+        assert_eq!(2, apart.len());
+        assert!(TOLERANCE.equals(6f64, area(&apart, &plane)));
+    }
+
+    fn circle(center:(f64, f64), radius:f64, count:usize) -> PolyLine {
+        PolyLine::new((0..=count)
+            .map(|k| {
+                let angle = 2f64 * std::f64::consts::PI * (k % count) as f64 / count as f64;
+                Point3d::new(center.0 + radius * angle.cos(), center.1 + radius * angle.sin(), 0f64)
+            })
+            .collect())
+    }
+
+    #[test]
+    pub fn fine_curves_keep_their_shape() {
+        let plane = Plane::WORLDXY;
+        let a = [circle((0f64, 0f64), 10f64, 1000)];
+
+        let united = union(&a, &a, &plane, &TOLERANCE);
+        assert_eq!(1, united.len());
+        assert!(a[0].points().iter().all(|point| united[0].closest_point(point).distance_to(point) <= TOLERANCE.absolute));
+
+        // Corners that are not straight enough to tidy away stay, so the areas add up to within the tolerance
+        let (a, b) = ([circle((0f64, 0f64), 10f64, 200)], [circle((5f64, 0f64), 10f64, 200)]);
+        let total = area(&union(&a, &b, &plane, &TOLERANCE), &plane) + area(&intersection(&a, &b, &plane, &TOLERANCE), &plane);
+        assert!((total - area(&a, &plane) - area(&b, &plane)).abs() < TOLERANCE.absolute);
+    }
+
+    #[test]
+    pub fn exact_tolerance() {
+        let plane = Plane::WORLDXY;
+        let a = [rectangle(&plane, (0f64, 0f64), (2f64, 2f64))];
+        let b = [rectangle(&plane, (1f64, 1f64), (3f64, 3f64))];
+
+        let united = union(&a, &b, &plane, &Tolerance::EXACT);
+        assert_eq!(1, united.len());
+        assert_eq!(9, united[0].point_count());
+        assert!(TOLERANCE.equals(7f64, area(&united, &plane)));
+        assert!(TOLERANCE.equals(1f64, area(&intersection(&a, &b, &plane, &Tolerance::EXACT), &plane)));
+    }
+
+    #[test]
+    pub fn coincident_edges_and_vertices() {
+        let plane = Plane::WORLDXY;
+        let a = [rectangle(&plane, (0f64, 0f64), (1f64, 1f64))];
+        let b = [rectangle(&plane, (1f64, 0f64), (2f64, 1f64))];
+
+        // Sharing a whole edge, the union is one rectangle with the shared edge gone
+        let united = union(&a, &b, &plane, &TOLERANCE);
+        assert_eq!(1, united.len());
+        assert_eq!(5, united[0].point_count());
+        assert!(TOLERANCE.equals(2f64, area(&united, &plane)));
+        assert!(intersection(&a, &b, &plane, &TOLERANCE).is_empty());
+
+        // The same region overlaps itself exactly
+        assert!(TOLERANCE.equals(1f64, area(&union(&a, &a, &plane, &TOLERANCE), &plane)));
+        assert!(TOLERANCE.equals(1f64, area(&intersection(&a, &a, &plane, &TOLERANCE), &plane)));
+        assert!(difference(&a, &a, &plane, &TOLERANCE).is_empty());
+
+        // Touching only at a corner, the islands stay apart
+        let corner = [rectangle(&plane, (1f64, 1f64), (2f64, 2f64))];
+        assert_eq!(2, union(&a, &corner, &plane, &TOLERANCE).len());
+    }
+
+    #[test]
+    pub fn holes_and_islands() {
+        let plane = Plane::WORLDXY;
+        // The hole winds the same way as the outside, which makes no difference
+        let frame = [rectangle(&plane, (0f64, 0f64), (4f64, 4f64)), rectangle(&plane, (1f64, 1f64), (3f64, 3f64))];
+        let bar = [rectangle(&plane, (-1f64, 1.5f64), (5f64, 2.5f64))];
+
+        let united = union(&frame, &bar, &plane, &TOLERANCE);
+        assert_eq!(3, united.len());
+        assert!(TOLERANCE.equals(16f64, area(&united, &plane)));
+        assert_eq!(2, united.iter().filter(|polyline| polyline.orientation(&plane, &TOLERANCE) == Some(Orientation::Clockwise)).count());
+
+        let cut = difference(&frame, &bar, &plane, &TOLERANCE);
+        assert_eq!(2, cut.len());
+        assert!(TOLERANCE.equals(10f64, area(&cut, &plane)));
+
+        // Filling the hole exactly
+        let plug = [rectangle(&plane, (1f64, 1f64), (3f64, 3f64))];
+        let filled = union(&frame, &plug, &plane, &TOLERANCE);
+        assert_eq!(1, filled.len());
+        assert!(TOLERANCE.equals(16f64, area(&filled, &plane)));
+        assert!(intersection(&frame, &plug, &plane, &TOLERANCE).is_empty());
+    }
+
+    #[test]
+    pub fn booleans_in_a_tilted_plane() {
+        let plane = Plane::from_point_normal(Point3d::new(0f64, 0f64, 5f64), Vector3d::new(1f64, 1f64, 1f64)).unwrap();
+        let a = [rectangle(&plane, (0f64, 0f64), (2f64, 2f64))];
+        let b = [rectangle(&plane, (1f64, 1f64), (3f64, 3f64))];
+
+        let united = union(&a, &b, &plane, &TOLERANCE);
+        assert!(TOLERANCE.equals(7f64, area(&united, &plane)));
+        assert!(united[0].points().iter().all(|point| TOLERANCE.is_zero(plane.distance_to(point))));
+    }
+
+}
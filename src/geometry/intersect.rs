@@ -0,0 +1,524 @@
+use std::f64::consts::PI;
+
+use crate::IsValid::IsValid;
+use crate::Tolerance::Tolerance;
+use crate::geometry::basics::BoundingBox::BoundingBox;
+use crate::geometry::basics::Circle::Circle;
+use crate::geometry::basics::InfiniteLine::InfiniteLine;
+use crate::geometry::basics::Interval::Interval;
+use crate::geometry::basics::Line::Line;
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::Vector3d::Vector3d;
+
+// Line parameters are always normalized, 0 at the start and 1 at the end of the line.
+// When `limit_to_segments` is true, results beyond the ends of a line are discarded.
+// Lines count as parallel when they drift apart by no more than the absolute tolerance over their length,
+// so long lines meeting at a shallow angle still cross. Planes, having no length, are measured over a unit distance.
+
+/// The result of intersecting two lines
+pub enum LineLineIntersection {
+    /// The segments do not meet, their closest approach lies beyond the end of one of them
+    None,
+    /// The lines cross. `a` and `b` are the parameters of the point on each line
+    Point { point: Point3d, a: f64, b: f64 },
+    /// The lines pass each other without meeting. `a` and `b` are the parameters of closest approach
+    Skew { a: f64, b: f64, distance: f64 },
+    /// The lines are parallel and apart
+    Parallel { distance: f64 },
+    /// The lines lie along each other. `a` and `b` are the ranges of each line that are shared
+    Overlap { a: Interval, b: Interval },
+}
+
+/// The result of intersecting a line with a plane
+pub enum LinePlaneIntersection {
+    /// The segment stops short of the plane
+    None,
+    /// The line pierces the plane at `t`, and (`u`, `v`) on the plane
+    Point { point: Point3d, t: f64, u: f64, v: f64 },
+    /// The line is parallel to the plane and apart from it
+    Parallel { distance: f64 },
+    /// The line lies in the plane
+    Overlap,
+}
+
+/// The result of intersecting two planes
+pub enum PlanePlaneIntersection {
+    /// The planes meet along a line
+    Line(InfiniteLine),
+    /// The planes are parallel and apart
+    Parallel { distance: f64 },
+    /// The planes are the same plane
+    Overlap,
+}
+
+/// The result of intersecting three planes
+pub enum PlanePlanePlaneIntersection {
+    /// The planes meet at a single point
+    Point(Point3d),
+    /// At least two of the planes are parallel, or all three share a line
+    None,
+}
+
+/// A place where a line meets a circle
+#[derive(Copy, Clone)]
+pub struct LineCircleHit {
+    /// The point on both curves
+    pub point: Point3d,
+    /// The parameter on the line
+    pub t: f64,
+    /// The angle on the circle in radians, from 0 to 2 PI
+    pub angle: f64,
+}
+
+/// The result of intersecting a line with a circle
+pub enum LineCircleIntersection {
+    /// The line misses the circle
+    None,
+    /// The line touches the circle once, either tangent to it or piercing its plane on the circle
+    Point(LineCircleHit),
+    /// The line passes through the circle, the hits are in order along the line
+    TwoPoints(LineCircleHit, LineCircleHit),
+}
+
+/// The result of intersecting a line with a bounding box
+pub enum LineBoxIntersection {
+    /// The line misses the box
+    None,
+    /// The line touches the box at a single point
+    Point { point: Point3d, t: f64 },
+    /// The range of the line inside the box
+    Overlap { t: Interval },
+}
+
+/// The allowed slack in normalized parameters for a line of the given length
+fn parameter_slack(length:f64, tolerance:&Tolerance) -> f64 {
+    tolerance.absolute / length
+}
+
+fn in_segment(t:f64, slack:f64) -> bool {
+    t >= -slack && t <= 1f64 + slack
+}
+
+/// Intersects two lines, reporting crossings, skew approach, parallel lines and overlaps
+pub fn line_line(a:&Line, b:&Line, tolerance:&Tolerance, limit_to_segments:bool) -> LineLineIntersection {
+    let da = a.direction();
+    let db = b.direction();
+    if da.is_tiny(tolerance) || db.is_tiny(tolerance) {
+        return LineLineIntersection::None;
+    }
+
+    if da.cross(&db).length() <= tolerance.absolute * da.length().min(db.length()) {
+        let distance = a.closest_point(&b.start, false).distance_to(&b.start);
+        if distance > tolerance.absolute {
+            return LineLineIntersection::Parallel { distance };
+        }
+
+        let mut low = a.closest_parameter(&b.start);
+        let mut high = a.closest_parameter(&b.end);
+        if low > high {
+            std::mem::swap(&mut low, &mut high);
+        }
+
+        if limit_to_segments {
+            low = low.max(0f64);
+            high = high.min(1f64);
+            if high < low - parameter_slack(a.length(), tolerance) {
+                return LineLineIntersection::None;
+            }
+        }
+
+        let on_b = Interval::new(b.closest_parameter(&a.point_at(low)),
+                                 b.closest_parameter(&a.point_at(high)));
+        return LineLineIntersection::Overlap { a: Interval::new(low, high), b: on_b };
+    }
+
+    let r = a.start - b.start;
+    let (aa, ab, bb) = (da.dot(&da), da.dot(&db), db.dot(&db));
+    let (ar, br) = (da.dot(&r), db.dot(&r));
+    let denominator = aa * bb - ab * ab;
+
+    let s = (ab * br - bb * ar) / denominator;
+    let t = (aa * br - ab * ar) / denominator;
+
+    if limit_to_segments &&
+       (!in_segment(s, parameter_slack(a.length(), tolerance)) ||
+        !in_segment(t, parameter_slack(b.length(), tolerance))) {
+        return LineLineIntersection::None;
+    }
+
+    let on_a = a.point_at(s);
+    let on_b = b.point_at(t);
+    let distance = on_a.distance_to(&on_b);
+    if distance <= tolerance.absolute {
+        LineLineIntersection::Point { point: on_a.interpolate(&on_b, 0.5f64), a: s, b: t }
+    } else {
+        LineLineIntersection::Skew { a: s, b: t, distance }
+    }
+}
+
+/// Intersects a line with a plane
+pub fn line_plane(line:&Line, plane:&Plane, tolerance:&Tolerance, limit_to_segment:bool) -> LinePlaneIntersection {
+    let direction = line.direction();
+    let normal = Vector3d::unitize(&plane.z);
+
+    if direction.is_tiny(tolerance) || direction.dot(&normal).abs() <= tolerance.absolute {
+        let distance = (line.start - plane.origin).dot(&normal).abs();
+        if distance <= tolerance.absolute {
+            return LinePlaneIntersection::Overlap;
+        }
+        return LinePlaneIntersection::Parallel { distance };
+    }
+
+    let t = (plane.origin - line.start).dot(&normal) / direction.dot(&normal);
+    if limit_to_segment && !in_segment(t, parameter_slack(line.length(), tolerance)) {
+        return LinePlaneIntersection::None;
+    }
+
+    let point = line.point_at(t);
+    let offset = point - plane.origin;
+    LinePlaneIntersection::Point { point, t, u: offset.dot(&plane.x), v: offset.dot(&plane.y) }
+}
+
+/// Intersects two planes. The resulting line has a unit direction
+pub fn plane_plane(a:&Plane, b:&Plane, tolerance:&Tolerance) -> PlanePlaneIntersection {
+    let na = Vector3d::unitize(&a.z);
+    let nb = Vector3d::unitize(&b.z);
+
+    if na.cross(&nb).length() <= tolerance.absolute {
+        let distance = (b.origin - a.origin).dot(&na).abs();
+        if distance <= tolerance.absolute {
+            return PlanePlaneIntersection::Overlap;
+        }
+        return PlanePlaneIntersection::Parallel { distance };
+    }
+
+    // Working relative to a's origin keeps the numbers small, and puts a at height 0
+    let height = (b.origin - a.origin).dot(&nb);
+    let ab = na.dot(&nb);
+    let denominator = 1f64 - ab * ab;
+
+    let offset = na * (-height * ab / denominator) + nb * (height / denominator);
+    let direction = Vector3d::unitize(&na.cross(&nb));
+
+    PlanePlaneIntersection::Line(InfiniteLine::new(a.origin + offset, direction))
+}
+
+/// Intersects three planes
+pub fn plane_plane_plane(a:&Plane, b:&Plane, c:&Plane, tolerance:&Tolerance) -> PlanePlanePlaneIntersection {
+    let line = match plane_plane(a, b, tolerance) {
+        PlanePlaneIntersection::Line(line) => line,
+        _ => return PlanePlanePlaneIntersection::None,
+    };
+
+    let on_line = Line::new(line.origin, line.point_at(1f64));
+    match line_plane(&on_line, c, tolerance, false) {
+        LinePlaneIntersection::Point { point, .. } => PlanePlanePlaneIntersection::Point(point),
+        _ => PlanePlanePlaneIntersection::None,
+    }
+}
+
+/// Intersects a line with a circle. A line with no length meets it only if it lies on the circle, at parameter 0
+pub fn line_circle(line:&Line, circle:&Circle, tolerance:&Tolerance, limit_to_segment:bool) -> LineCircleIntersection {
+    let plane = circle.plane;
+    let hit = |t:f64| {
+        let point = line.point_at(t);
+        let offset = point - plane.origin;
+        let mut angle = offset.dot(&plane.y).atan2(offset.dot(&plane.x));
+        if angle < 0f64 {
+            angle += 2f64 * PI;
+        }
+        LineCircleHit { point, t, angle }
+    };
+
+    // A line with no length has no direction to measure parameters along, it can only be a point on the circle
+    if line.direction().is_tiny(tolerance) {
+        let on_circle = tolerance.is_zero(plane.distance_to(&line.start)) &&
+                        tolerance.equals(line.start.distance_to(&circle.center()), circle.radius);
+        return match on_circle {
+            true => LineCircleIntersection::Point(hit(0f64)),
+            false => LineCircleIntersection::None,
+        };
+    }
+
+    let slack = parameter_slack(line.length(), tolerance);
+    match line_plane(line, &plane, tolerance, limit_to_segment) {
+        LinePlaneIntersection::None | LinePlaneIntersection::Parallel { .. } => LineCircleIntersection::None,
+        LinePlaneIntersection::Point { point, t, .. } => {
+            if tolerance.equals(point.distance_to(&circle.center()), circle.radius) {
+                LineCircleIntersection::Point(hit(t))
+            } else {
+                LineCircleIntersection::None
+            }
+        },
+        LinePlaneIntersection::Overlap => {
+            let closest = line.closest_parameter(&circle.center());
+            let height = line.point_at(closest).distance_to(&circle.center());
+
+            if height > circle.radius + tolerance.absolute {
+                return LineCircleIntersection::None;
+            }
+
+            let keep = |t:f64| !limit_to_segment || in_segment(t, slack);
+            if height >= circle.radius - tolerance.absolute {
+                return match keep(closest) {
+                    true => LineCircleIntersection::Point(hit(closest)),
+                    false => LineCircleIntersection::None,
+                };
+            }
+
+            let half_chord = (circle.radius * circle.radius - height * height).sqrt() / line.length();
+            let (first, second) = (closest - half_chord, closest + half_chord);
+            match (keep(first), keep(second)) {
+                (true, true) => LineCircleIntersection::TwoPoints(hit(first), hit(second)),
+                (true, false) => LineCircleIntersection::Point(hit(first)),
+                (false, true) => LineCircleIntersection::Point(hit(second)),
+                (false, false) => LineCircleIntersection::None,
+            }
+        },
+    }
+}
+
+/// Intersects a line with a bounding box using the slab method. An empty or unset box is never hit
+pub fn line_box(line:&Line, bounds:&BoundingBox, tolerance:&Tolerance, limit_to_segment:bool) -> LineBoxIntersection {
+    if !bounds.is_valid() {
+        return LineBoxIntersection::None;
+    }
+
+    let direction = line.direction();
+    let (min, max) = (bounds.min(), bounds.max());
+
+    let mut near = f64::NEG_INFINITY;
+    let mut far = f64::INFINITY;
+    if limit_to_segment {
+        near = 0f64;
+        far = 1f64;
+    }
+
+    let slabs = [(line.start.x, direction.x, min.x, max.x),
+                 (line.start.y, direction.y, min.y, max.y),
+                 (line.start.z, direction.z, min.z, max.z)];
+
+    for (start, step, low, high) in slabs {
+        if step == 0f64 {
+            if start < low - tolerance.absolute || start > high + tolerance.absolute {
+                return LineBoxIntersection::None;
+            }
+            continue;
+        }
+
+        let (mut enter, mut exit) = ((low - start) / step, (high - start) / step);
+        if enter > exit {
+            std::mem::swap(&mut enter, &mut exit);
+        }
+        near = near.max(enter);
+        far = far.min(exit);
+    }
+
+    let slack = parameter_slack(direction.length(), tolerance);
+    if near > far + slack {
+        return LineBoxIntersection::None;
+    }
+
+    if far - near <= slack {
+        let t = (near + far) / 2f64;
+        return LineBoxIntersection::Point { point: line.point_at(t), t };
+    }
+
+    LineBoxIntersection::Overlap { t: Interval::new(near, far) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EpsilonEquals::EpsilonEquals;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
+
+    #[test]
+    pub fn line_line_crossing() {
+        let a = Line::new(Point3d::new(0f64, 0f64, 0f64), Point3d::new(10f64, 0f64, 0f64));
+        let b = Line::new(Point3d::new(5f64, -5f64, 0f64), Point3d::new(5f64, 5f64, 0f64));
+
+        match line_line(&a, &b, &TOLERANCE, true) {
+            LineLineIntersection::Point { point, a, b } => {
+                assert!(point.epsilon_equals(&Point3d::new(5f64, 0f64, 0f64), &TOLERANCE));
+                assert!(TOLERANCE.equals(0.5f64, a));
+                assert!(TOLERANCE.equals(0.5f64, b));
+            },
+            _ => panic!("Expected a crossing"),
+        }
+    }
+
+    #[test]
+    pub fn line_line_skew_and_limits() {
+        let a = Line::new(Point3d::new(0f64, 0f64, 0f64), Point3d::new(10f64, 0f64, 0f64));
+        let b = Line::new(Point3d::new(5f64, -5f64, 2f64), Point3d::new(5f64, 5f64, 2f64));
+
+        match line_line(&a, &b, &TOLERANCE, true) {
+            LineLineIntersection::Skew { distance, .. } => assert!(TOLERANCE.equals(2f64, distance)),
+            _ => panic!("Expected skew lines"),
+        }
+
+        let short = Line::new(Point3d::new(20f64, -5f64, 0f64), Point3d::new(20f64, -1f64, 0f64));
+        assert!(matches!(line_line(&a, &short, &TOLERANCE, true), LineLineIntersection::None));
+        assert!(matches!(line_line(&a, &short, &TOLERANCE, false), LineLineIntersection::Point { .. }));
+    }
+
+    #[test]
+    pub fn line_line_parallel_and_overlap() {
+        let a = Line::new(Point3d::new(0f64, 0f64, 0f64), Point3d::new(10f64, 0f64, 0f64));
+        let apart = Line::new(Point3d::new(0f64, 3f64, 0f64), Point3d::new(10f64, 3f64, 0f64));
+        assert!(matches!(line_line(&a, &apart, &TOLERANCE, true), LineLineIntersection::Parallel { .. }));
+
+        let along = Line::new(Point3d::new(15f64, 0f64, 0f64), Point3d::new(5f64, 0f64, 0f64));
+        match line_line(&a, &along, &TOLERANCE, true) {
+            LineLineIntersection::Overlap { a, b } => {
+                assert!(a.epsilon_equals(&Interval::new(0.5f64, 1f64), &TOLERANCE));
+                assert!(b.epsilon_equals(&Interval::new(1f64, 0.5f64), &TOLERANCE));
+            },
+            _ => panic!("Expected an overlap"),
+        }
+    }
+
+    #[test]
+    pub fn shallow_crossings_are_not_parallel() {
+        // Well inside the angle tolerance, but the ends are much further apart than the absolute tolerance
+        let a = Line::new(Point3d::new(0f64, 0f64, 0f64), Point3d::new(10f64, 0f64, 0f64));
+        let b = Line::new(Point3d::new(0f64, -0.04f64, 0f64), Point3d::new(10f64, 0.04f64, 0f64));
+        match line_line(&a, &b, &TOLERANCE, true) {
+            LineLineIntersection::Point { point, .. } => assert!(point.epsilon_equals(&Point3d::new(5f64, 0f64, 0f64), &TOLERANCE)),
+            _ => panic!("Expected a point"),
+        }
+
+        let slope = (0.5f64).to_radians().tan() * 100f64;
+        let long = Line::new(Point3d::new(-100f64, 0f64, -slope), Point3d::new(100f64, 0f64, slope));
+        match line_plane(&long, &Plane::WORLDXY, &TOLERANCE, true) {
+            LinePlaneIntersection::Point { point, .. } => assert!(point.epsilon_equals(&Point3d::ORIGIN, &TOLERANCE)),
+            _ => panic!("Expected a point"),
+        }
+    }
+
+    #[test]
+    pub fn line_plane_point() {
+        let line = Line::new(Point3d::new(1f64, 2f64, -5f64), Point3d::new(1f64, 2f64, 5f64));
+
+        match line_plane(&line, &Plane::WORLDXY, &TOLERANCE, true) {
+            LinePlaneIntersection::Point { point, t, u, v } => {
+                assert!(point.epsilon_equals(&Point3d::new(1f64, 2f64, 0f64), &TOLERANCE));
+                assert!(TOLERANCE.equals(0.5f64, t));
+                assert!(TOLERANCE.equals(1f64, u));
+                assert!(TOLERANCE.equals(2f64, v));
+            },
+            _ => panic!("Expected a point"),
+        }
+
+        let flat = Line::new(Point3d::new(0f64, 0f64, 1f64), Point3d::new(1f64, 0f64, 1f64));
+        assert!(matches!(line_plane(&flat, &Plane::WORLDXY, &TOLERANCE, true), LinePlaneIntersection::Parallel { .. }));
+
+        let inside = Line::new(Point3d::new(0f64, 0f64, 0f64), Point3d::new(1f64, 0f64, 0f64));
+        assert!(matches!(line_plane(&inside, &Plane::WORLDXY, &TOLERANCE, true), LinePlaneIntersection::Overlap));
+    }
+
+    #[test]
+    pub fn plane_plane_line() {
+        let raised = Plane::new(Point3d::new(0f64, 0f64, 3f64), Vector3d::XAXIS, Vector3d::ZAXIS).unwrap();
+
+        match plane_plane(&Plane::WORLDXY, &raised, &TOLERANCE) {
+            PlanePlaneIntersection::Line(line) => {
+                assert!(line.direction.is_parallel_to(&Vector3d::XAXIS, &TOLERANCE));
+                assert!(TOLERANCE.is_zero(line.origin.z));
+                assert!(TOLERANCE.is_zero(line.origin.y));
+            },
+            _ => panic!("Expected a line"),
+        }
+
+        let above = Plane::new(Point3d::new(0f64, 0f64, 3f64), Vector3d::XAXIS, Vector3d::YAXIS).unwrap();
+        assert!(matches!(plane_plane(&Plane::WORLDXY, &above, &TOLERANCE), PlanePlaneIntersection::Parallel { .. }));
+    }
+
+    #[test]
+    pub fn three_planes() {
+        let x = Plane::new(Point3d::new(1f64, 0f64, 0f64), Vector3d::YAXIS, Vector3d::ZAXIS).unwrap();
+        let y = Plane::new(Point3d::new(0f64, 2f64, 0f64), Vector3d::ZAXIS, Vector3d::XAXIS).unwrap();
+        let z = Plane::new(Point3d::new(0f64, 0f64, 3f64), Vector3d::XAXIS, Vector3d::YAXIS).unwrap();
+
+        match plane_plane_plane(&x, &y, &z, &TOLERANCE) {
+            PlanePlanePlaneIntersection::Point(point) => {
+                assert!(point.epsilon_equals(&Point3d::new(1f64, 2f64, 3f64), &TOLERANCE));
+            },
+            PlanePlanePlaneIntersection::None => panic!("Expected a point"),
+        }
+    }
+
+    #[test]
+    pub fn line_circle_two_points() {
+        let circle = Circle::new(Point3d::ORIGIN, 5f64).unwrap();
+        let line = Line::new(Point3d::new(-10f64, 0f64, 0f64), Point3d::new(10f64, 0f64, 0f64));
+
+        match line_circle(&line, &circle, &TOLERANCE, true) {
+            LineCircleIntersection::TwoPoints(first, second) => {
+                assert!(first.point.epsilon_equals(&Point3d::new(-5f64, 0f64, 0f64), &TOLERANCE));
+                assert!(TOLERANCE.equals(0.25f64, first.t));
+                assert!(TOLERANCE.angle_equals(PI, first.angle));
+                assert!(second.point.epsilon_equals(&Point3d::new(5f64, 0f64, 0f64), &TOLERANCE));
+                assert!(TOLERANCE.angle_equals(0f64, second.angle));
+            },
+            _ => panic!("Expected two points"),
+        }
+
+        let tangent = Line::new(Point3d::new(-10f64, 5f64, 0f64), Point3d::new(10f64, 5f64, 0f64));
+        assert!(matches!(line_circle(&tangent, &circle, &TOLERANCE, true), LineCircleIntersection::Point(_)));
+
+        let piercing = Line::new(Point3d::new(0f64, 5f64, -1f64), Point3d::new(0f64, 5f64, 1f64));
+        assert!(matches!(line_circle(&piercing, &circle, &TOLERANCE, true), LineCircleIntersection::Point(_)));
+    }
+
+    #[test]
+    pub fn line_circle_without_length() {
+        let circle = Circle::new(Point3d::ORIGIN, 5f64).unwrap();
+
+        let on = Line::new(Point3d::new(0f64, 5f64, 0f64), Point3d::new(0f64, 5f64, 0f64));
+        match line_circle(&on, &circle, &TOLERANCE, true) {
+            LineCircleIntersection::Point(hit) => {
+                assert_eq!(0f64, hit.t);
+                assert!(hit.point.epsilon_equals(&Point3d::new(0f64, 5f64, 0f64), &TOLERANCE));
+                assert!(TOLERANCE.angle_equals(PI / 2f64, hit.angle));
+            },
+            _ => panic!("Expected a point"),
+        }
+
+        let inside = Line::new(Point3d::new(1f64, 1f64, 0f64), Point3d::new(1f64, 1f64, 0f64));
+        assert!(matches!(line_circle(&inside, &circle, &TOLERANCE, false), LineCircleIntersection::None));
+    }
+
+    #[test]
+    pub fn line_box_slab() {
+        let bounds = BoundingBox::new(Point3d::ORIGIN, Point3d::new(10f64, 10f64, 10f64));
+        let line = Line::new(Point3d::new(-10f64, 5f64, 5f64), Point3d::new(20f64, 5f64, 5f64));
+
+        match line_box(&line, &bounds, &Tolerance::EXACT, true) {
+            LineBoxIntersection::Overlap { t } => {
+                assert!(t.epsilon_equals(&Interval::new(1f64 / 3f64, 2f64 / 3f64), &TOLERANCE));
+            },
+            _ => panic!("Expected an overlap"),
+        }
+
+        let miss = Line::new(Point3d::new(-10f64, 15f64, 5f64), Point3d::new(20f64, 15f64, 5f64));
+        assert!(matches!(line_box(&miss, &bounds, &TOLERANCE, true), LineBoxIntersection::None));
+
+        let short = Line::new(Point3d::new(-10f64, 5f64, 5f64), Point3d::new(-5f64, 5f64, 5f64));
+        assert!(matches!(line_box(&short, &bounds, &TOLERANCE, true), LineBoxIntersection::None));
+        assert!(matches!(line_box(&short, &bounds, &TOLERANCE, false), LineBoxIntersection::Overlap { .. }));
+
+        let corner = Line::new(Point3d::new(-5f64, -5f64, 5f64), Point3d::new(5f64, -15f64, 5f64));
+        assert!(matches!(line_box(&corner, &bounds, &TOLERANCE, true), LineBoxIntersection::None));
+        let touch = Line::new(Point3d::new(-5f64, 5f64, 5f64), Point3d::new(5f64, -5f64, 5f64));
+        assert!(matches!(line_box(&touch, &bounds, &TOLERANCE, true), LineBoxIntersection::Point { .. }));
+
+        let diagonal = Line::new(Point3d::ORIGIN, Point3d::new(1f64, 1f64, 1f64));
+        assert!(matches!(line_box(&diagonal, &BoundingBox::EMPTY, &TOLERANCE, false), LineBoxIntersection::None));
+        assert!(matches!(line_box(&diagonal, &BoundingBox::UNSET, &TOLERANCE, false), LineBoxIntersection::None));
+    }
+
+}
@@ -1 +1,7 @@
-pub mod basics;
\ No newline at end of file
+pub(crate) mod arrangement;
+pub mod basics;
+pub mod boolean;
+pub mod intersect;
+pub mod offset;
+pub mod polygon;
+pub mod Transform;
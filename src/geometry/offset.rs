@@ -0,0 +1,381 @@
+use std::f64::consts::PI;
+
+use crate::Tolerance::Tolerance;
+use crate::geometry::arrangement::{signed_area, Arrangement, Edge};
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Point2d::Point2d;
+use crate::geometry::basics::PolyLine::PolyLine;
+use crate::geometry::basics::Vector2d::Vector2d;
+
+// Offsetting works in the 2D coordinates of the plane. The offset of every segment and join is laid down as one raw loop
+// winding anticlockwise around the area to keep, with inside corners going back through the corner so that nothing is missed.
+// Where the offset collapses the raw loop turns inside out, so the answer is everything it winds around a positive number of times.
+// The raw loop is cut wherever it crosses or touches itself and the pieces bounding that area are stitched together.
+
+/// How the offset sides meet around the outside of a corner
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Join {
+    /// Extends the sides until they meet in a point. Where that point would be further than `limit` times
+    /// the distance from the corner, the corner is bevelled instead
+    Miter { limit: f64 },
+    /// Joins the sides with an arc around the corner
+    Round,
+    /// Joins the sides with a straight cut across the corner
+    Bevel,
+}
+
+/// How the ends of an open polyline are finished
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EndCap {
+    /// Offsets one side only, giving open polylines.
+    /// A positive distance offsets to the left looking down the plane's Z axis
+    Open,
+    /// Outlines both sides, closed with a straight line across each end
+    Butt,
+    /// Outlines both sides, closed with a square reaching the distance past each end
+    Square,
+    /// Outlines both sides, closed with a semicircle around each end
+    Round,
+}
+
+/// Offsets a polyline lying in a plane by `distance`, returning every loop or piece the offset breaks into.
+///
+/// Closed polylines grow for a positive distance and shrink for a negative one, whichever way they wind, and `cap` is ignored.
+/// Open polylines are offset as `cap` describes, and a one sided offset is trimmed where it comes too close to other parts
+/// of the polyline. Loops come out anticlockwise looking down the plane's Z axis, except for holes which are clockwise.
+/// Points off the plane are projected onto it first. A distance of zero returns the polyline unchanged,
+/// and an offset that collapses completely returns nothing. Round joins and caps stay within the tolerance of a true arc,
+/// but never use more than one point per degree
+pub fn polyline(polyline:&PolyLine, plane:&Plane, distance:f64, join:Join, cap:EndCap, tolerance:&Tolerance) -> Vec<PolyLine> {
+    if distance == 0f64 {
+        return vec![polyline.clone()];
+    }
+
+    let closed = polyline.is_closed(tolerance);
+    let mut points:Vec<Point2d> = polyline.remove_duplicates(tolerance).points().iter()
+        .map(|point| plane.project(point))
+        .collect();
+    if closed {
+        points.pop();
+    }
+    if points.len() < 2 || (closed && points.len() < 3) || !distance.is_finite() {
+        return Vec::new();
+    }
+
+    let radius = distance.abs();
+    let slack = tolerance.absolute.clamp(radius * 1e-6, radius / 2f64);
+    let builder = PathBuilder { join, radius, sagitta:slack / 2f64 };
+
+    let mut raw = RawLoop { points:Vec::new(), sides:Vec::new() };
+    if closed {
+        // Offsetting to the right of an anticlockwise loop grows it
+        if signed_area(points.iter().copied()) < 0f64 {
+            points.reverse();
+        }
+        builder.chain(&points, -distance, Side::Right, true, &mut raw);
+    } else {
+        // Down the right side, around the end, back up the left side and around the start
+        let reversed:Vec<Point2d> = points.iter().rev().copied().collect();
+        let outward = |from:Point2d, to:Point2d| Vector2d::unitize(&(to - from));
+        let cap_style = if cap == EndCap::Open { EndCap::Butt } else { cap };
+
+        builder.chain(&points, -radius, Side::Right, false, &mut raw);
+        builder.cap(points[points.len() - 1], outward(points[points.len() - 2], points[points.len() - 1]), cap_style, &mut raw);
+        builder.chain(&reversed, -radius, Side::Left, false, &mut raw);
+        builder.cap(points[0], outward(points[1], points[0]), cap_style, &mut raw);
+    }
+
+    let arrangement = Arrangement::new(&raw.edges(), slack);
+    let mut keep = arrangement.boundary(|point| arrangement.winding(point, |_| true) > 0);
+
+    // A one sided offset is the part of the outline that came from that side
+    if !closed && cap == EndCap::Open {
+        let wanted = if distance > 0f64 { Side::Left } else { Side::Right };
+        for (keep, piece) in keep.iter_mut().zip(&arrangement.pieces) {
+            *keep &= piece.tag == wanted;
+        }
+    }
+
+    arrangement.stitch(&keep).into_iter()
+        .filter(|points| length(points) > slack)
+        .map(|points| PolyLine::new(points.iter().map(|point| plane.lift(point)).collect()))
+        .collect()
+}
+
+/// Which part of the raw loop an edge came from
+#[derive(Copy, Clone, PartialEq)]
+enum Side {
+    /// The offset to the left of the original, looking along it
+    Left,
+    /// The offset to the right of the original
+    Right,
+    /// An end cap
+    End,
+}
+
+/// The smallest angle between points on a round join or cap, so a tight tolerance cannot blow up the size of the result
+const MIN_ARC_STEP:f64 = PI / 180f64;
+
+/// Lays down the raw offset of chains of segments, with their joins and end caps
+struct PathBuilder {
+    join:Join,
+    radius:f64,
+    /// The furthest any chord of a round join or cap may stray from the true arc
+    sagitta:f64,
+}
+
+impl PathBuilder {
+
+    /// Adds the offset of the chain of `points` by `offset`, positive to the left
+    fn chain(&self, points:&[Point2d], offset:f64, side:Side, closed:bool, raw:&mut RawLoop) {
+        let count = points.len();
+        let directions:Vec<Vector2d> = (0..if closed { count } else { count - 1 })
+            .map(|k| Vector2d::unitize(&(points[(k + 1) % count] - points[k])))
+            .collect();
+
+        if closed {
+            for vertex in 0..count {
+                let before = (vertex + count - 1) % count;
+                self.join(points[vertex], directions[before], directions[vertex], offset, side, raw);
+            }
+            return;
+        }
+
+        raw.push(points[0] + directions[0].perpendicular() * offset, Side::End);
+        for vertex in 1..count - 1 {
+            self.join(points[vertex], directions[vertex - 1], directions[vertex], offset, side, raw);
+        }
+        raw.push(points[count - 1] + directions[count - 2].perpendicular() * offset, side);
+    }
+
+    /// Adds the points where the offset of the segment arriving at `corner` along `a` turns onto the one leaving along `b`
+    fn join(&self, corner:Point2d, a:Vector2d, b:Vector2d, offset:f64, side:Side, raw:&mut RawLoop) {
+        let (na, nb) = (a.perpendicular() * offset, b.perpendicular() * offset);
+        if (na - nb).length() <= self.sagitta {
+            raw.push(corner + na, side);
+            return;
+        }
+
+        // Turning towards the offset the two sides overlap. Going back through the corner leaves a small loop
+        // winding the wrong way, which the winding rule throws away
+        if a.cross(&b) * offset > 0f64 {
+            raw.push(corner + na, side);
+            raw.push(corner, side);
+            raw.push(corner + nb, side);
+            return;
+        }
+
+        match self.join {
+            Join::Miter { limit } => {
+                let miter = (na + nb) / (1f64 + a.dot(&b));
+                if miter.length() <= limit * self.radius {
+                    raw.push(corner + miter, side);
+                } else {
+                    raw.push(corner + na, side);
+                    raw.push(corner + nb, side);
+                }
+            },
+            Join::Bevel => {
+                raw.push(corner + na, side);
+                raw.push(corner + nb, side);
+            },
+            Join::Round => {
+                raw.push(corner + na, side);
+                self.arc(corner, na, -offset.signum() * a.angle_to(&b), side, raw);
+                raw.push(corner + nb, side);
+            },
+        }
+    }
+
+    /// Adds the points around the end of an open chain at `end`, leaving along `outward`,
+    /// from the right side of the chain to the left
+    fn cap(&self, end:Point2d, outward:Vector2d, cap:EndCap, raw:&mut RawLoop) {
+        let left = outward.perpendicular() * self.radius;
+        match cap {
+            EndCap::Square => {
+                raw.push(end - left + outward * self.radius, Side::End);
+                raw.push(end + left + outward * self.radius, Side::End);
+            },
+            EndCap::Round => self.arc(end, -left, PI, Side::End, raw),
+            EndCap::Open | EndCap::Butt => {},
+        }
+    }
+
+    /// Adds the points strictly between the ends of an arc about `center`, starting at `center + start`
+    /// and turning by `sweep` radians, positive anticlockwise
+    fn arc(&self, center:Point2d, start:Vector2d, sweep:f64, side:Side, raw:&mut RawLoop) {
+        let step = (2f64 * (1f64 - self.sagitta / self.radius).acos()).max(MIN_ARC_STEP);
+        let steps = (sweep.abs() / step).ceil().max(1f64) as usize;
+        for index in 1..steps {
+            raw.push(center + start.rotate(sweep * index as f64 / steps as f64), side);
+        }
+    }
+
+}
+
+/// The raw offset, before it is cut where it crosses itself
+struct RawLoop {
+    points:Vec<Point2d>,
+    /// Where the segment arriving at each point came from
+    sides:Vec<Side>,
+}
+
+impl RawLoop {
+
+    fn push(&mut self, point:Point2d, side:Side) {
+        self.points.push(point);
+        self.sides.push(side);
+    }
+
+    /// Returns the segments of the loop, each tagged with where it came from
+    fn edges(&self) -> Vec<Edge<Side>> {
+        let count = self.points.len();
+        (0..count)
+            .map(|k| Edge { start:self.points[k], end:self.points[(k + 1) % count], tag:self.sides[(k + 1) % count] })
+            .collect()
+    }
+
+}
+
+fn length(points:&[Point2d]) -> f64 {
+    points.windows(2).map(|pair| pair[0].distance_to(&pair[1])).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EpsilonEquals::EpsilonEquals;
+    use crate::geometry::basics::Point3d::Point3d;
+    use crate::geometry::basics::PolyLine::Orientation;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
+
+    fn polyline(points:&[(f64, f64)]) -> PolyLine {
+        PolyLine::new(points.iter().map(|(x, y)| Point3d::new(*x, *y, 0f64)).collect())
+    }
+
+    fn square(size:f64) -> PolyLine {
+        polyline(&[(0f64, 0f64), (size, 0f64), (size, size), (0f64, size), (0f64, 0f64)])
+    }
+
+    fn area(polyline:&PolyLine) -> f64 {
+        polyline.area(&TOLERANCE).unwrap()
+    }
+
+    #[test]
+    pub fn square_joins() {
+        let miter = super::polyline(&square(10f64), &Plane::WORLDXY, 1f64, Join::Miter { limit: 4f64 }, EndCap::Open, &TOLERANCE);
+        assert_eq!(1, miter.len());
+        assert!(TOLERANCE.equals(144f64, area(&miter[0])));
+        assert!(miter[0].bounding_box().min().epsilon_equals(&Point3d::new(-1f64, -1f64, 0f64), &TOLERANCE));
+
+        let bevel = super::polyline(&square(10f64), &Plane::WORLDXY, 1f64, Join::Bevel, EndCap::Open, &TOLERANCE);
+        assert!(TOLERANCE.equals(142f64, area(&bevel[0])));
+
+        // A miter limit below the square root of two bevels right angles
+        let limited = super::polyline(&square(10f64), &Plane::WORLDXY, 1f64, Join::Miter { limit: 1.2f64 }, EndCap::Open, &TOLERANCE);
+        assert!(TOLERANCE.equals(142f64, area(&limited[0])));
+
+        let round = super::polyline(&square(10f64), &Plane::WORLDXY, 1f64, Join::Round, EndCap::Open, &TOLERANCE);
+        assert!((140f64 + PI - area(&round[0])).abs() < 0.01f64);
+    }
+
+    #[test]
+    pub fn growing_ignores_winding() {
+        let mut clockwise = square(10f64);
+        clockwise.reverse();
+
+        let grown = super::polyline(&clockwise, &Plane::WORLDXY, 1f64, Join::Bevel, EndCap::Open, &TOLERANCE);
+        assert!(TOLERANCE.equals(142f64, area(&grown[0])));
+
+        let shrunk = super::polyline(&clockwise, &Plane::WORLDXY, -1f64, Join::Round, EndCap::Open, &TOLERANCE);
+        assert_eq!(1, shrunk.len());
+        assert!(TOLERANCE.equals(64f64, area(&shrunk[0])));
+    }
+
+    #[test]
+    pub fn shrinking_splits_and_collapses() {
+        let dumbbell = polyline(&[(0f64, 0f64), (10f64, 0f64), (10f64, 4f64), (14f64, 4f64), (14f64, 0f64), (24f64, 0f64),
+                                  (24f64, 10f64), (14f64, 10f64), (14f64, 6f64), (10f64, 6f64), (10f64, 10f64), (0f64, 10f64),
+                                  (0f64, 0f64)]);
+
+        let narrowed = super::polyline(&dumbbell, &Plane::WORLDXY, -0.5f64, Join::Miter { limit: 2f64 }, EndCap::Open, &TOLERANCE);
+        assert_eq!(1, narrowed.len());
+
+        let split = super::polyline(&dumbbell, &Plane::WORLDXY, -1.5f64, Join::Miter { limit: 2f64 }, EndCap::Open, &TOLERANCE);
+        assert_eq!(2, split.len());
+        assert!(split.iter().all(|loop_| loop_.is_closed(&TOLERANCE) && TOLERANCE.equals(49f64, area(loop_))));
+
+        assert!(super::polyline(&square(10f64), &Plane::WORLDXY, -6f64, Join::Round, EndCap::Open, &TOLERANCE).is_empty());
+    }
+
+    #[test]
+    pub fn exact_round_joins_stay_small() {
+        let triangle = polyline(&[(0f64, 0f64), (10f64, 0f64), (0f64, 10f64), (0f64, 0f64)]);
+        let grown = super::polyline(&triangle, &Plane::WORLDXY, 1f64, Join::Round, EndCap::Open, &Tolerance::EXACT);
+
+        // A full turn of corners at one point per degree
+        assert_eq!(1, grown.len());
+        assert!(grown[0].point_count() <= 370);
+    }
+
+    #[test]
+    pub fn open_caps() {
+        let line = polyline(&[(0f64, 0f64), (10f64, 0f64)]);
+        let outline = |cap:EndCap| super::polyline(&line, &Plane::WORLDXY, 1f64, Join::Round, cap, &TOLERANCE);
+
+        let butt = outline(EndCap::Butt);
+        assert_eq!(1, butt.len());
+        assert!(TOLERANCE.equals(20f64, area(&butt[0])));
+        assert!(TOLERANCE.equals(24f64, area(&outline(EndCap::Square)[0])));
+        assert!((20f64 + PI - area(&outline(EndCap::Round)[0])).abs() < 0.01f64);
+
+        // Only the outer side of a bend survives a one sided offset
+        let bend = polyline(&[(0f64, 0f64), (10f64, 0f64), (10f64, 10f64)]);
+        let right = super::polyline(&bend, &Plane::WORLDXY, -1f64, Join::Miter { limit: 2f64 }, EndCap::Open, &TOLERANCE);
+        assert_eq!(1, right.len());
+        assert!(TOLERANCE.equals(22f64, right[0].length()));
+
+        let left = super::polyline(&bend, &Plane::WORLDXY, 1f64, Join::Miter { limit: 2f64 }, EndCap::Open, &TOLERANCE);
+        assert_eq!(1, left.len());
+        assert!(TOLERANCE.equals(18f64, left[0].length()));
+    }
+
+    #[test]
+    pub fn offset_in_a_tilted_plane() {
+        let plane = Plane::from_point_normal(Point3d::new(0f64, 0f64, 5f64), Point3d::new(0f64, 1f64, 1f64).into()).unwrap();
+        let square = PolyLine::new(vec![plane.point_at(0f64, 0f64, 0f64), plane.point_at(4f64, 0f64, 0f64),
+                                        plane.point_at(4f64, 4f64, 0f64), plane.point_at(0f64, 4f64, 0f64),
+                                        plane.point_at(0f64, 0f64, 0f64)]);
+
+        let grown = super::polyline(&square, &plane, 1f64, Join::Miter { limit: 2f64 }, EndCap::Open, &TOLERANCE);
+        assert!(TOLERANCE.equals(36f64, area(&grown[0])));
+        assert!(grown[0].points().iter().all(|point| TOLERANCE.is_zero(plane.distance_to(point))));
+    }
+
+    #[test]
+    pub fn growing_closes_gaps() {
+        // The mouth of the C is one wide, so growing it by one shuts it and leaves a hole inside
+        let c = polyline(&[(0f64, 0f64), (10f64, 0f64), (10f64, 4.5f64), (8f64, 4.5f64), (8f64, 2f64), (2f64, 2f64), (2f64, 8f64),
+                           (8f64, 8f64), (8f64, 5.5f64), (10f64, 5.5f64), (10f64, 10f64), (0f64, 10f64), (0f64, 0f64)]);
+        let grown = super::polyline(&c, &Plane::WORLDXY, 1f64, Join::Miter { limit: 2f64 }, EndCap::Open, &TOLERANCE);
+
+        assert_eq!(2, grown.len());
+        assert!(TOLERANCE.equals(144f64, area(&grown[0])));
+        assert!(TOLERANCE.equals(16f64, area(&grown[1])));
+        assert!(grown[0].orientation(&Plane::WORLDXY, &TOLERANCE) == Some(Orientation::Anticlockwise));
+        assert!(grown[1].orientation(&Plane::WORLDXY, &TOLERANCE) == Some(Orientation::Clockwise));
+    }
+
+    #[test]
+    pub fn outlines_merge_where_they_overlap() {
+        let spiral = polyline(&[(0f64, 0f64), (10f64, 0f64), (10f64, 10f64), (0f64, 10f64), (0f64, 2f64), (8f64, 2f64), (8f64, 8f64)]);
+        let outline = super::polyline(&spiral, &Plane::WORLDXY, 1.5f64, Join::Miter { limit: 2f64 }, EndCap::Butt, &TOLERANCE);
+
+        // Around the outside, and the hole left in the middle of the spiral
+        assert_eq!(2, outline.len());
+        assert!(TOLERANCE.equals(166f64, area(&outline[0])));
+        assert!(TOLERANCE.equals(26f64, area(&outline[1])));
+    }
+
+}
@@ -0,0 +1,479 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::Tolerance::Tolerance;
+use crate::geometry::arrangement::{crossing_sign, signed_area};
+use crate::geometry::basics::Line2d::Line2d;
+use crate::geometry::basics::Plane::Plane;
+use crate::geometry::basics::Point2d::Point2d;
+use crate::geometry::basics::Point3d::Point3d;
+use crate::geometry::basics::PolyLine::PolyLine;
+
+// Queries on closed polylines as the outlines of flat regions. Everything works in the 2D coordinates of the plane,
+// so points off the plane are projected onto it first, and results on the plane are lifted back into 3D.
+
+/// Where a point lies relative to a closed polyline
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Containment {
+    Inside,
+    Outside,
+    /// Within tolerance of the polyline itself
+    Boundary,
+}
+
+/// A place where a polyline runs into itself
+#[derive(Copy, Clone)]
+pub struct SelfIntersection {
+    /// The index of the earlier segment
+    pub first:usize,
+    /// The index of the later segment
+    pub second:usize,
+    /// Where the segments meet, or where one starts running back along the other
+    pub point:Point3d,
+}
+
+/// The reasons a region cannot be triangulated
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TriangulationError {
+    /// The outline or one of the holes is not closed
+    NotClosed,
+    /// The region has no area, or crosses itself so that no triangle can be cut off it
+    Degenerate,
+}
+
+impl fmt::Display for TriangulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriangulationError::NotClosed => write!(f, "the outline or a hole is not closed"),
+            TriangulationError::Degenerate => write!(f, "the region has no area or crosses itself"),
+        }
+    }
+}
+
+impl std::error::Error for TriangulationError {}
+
+/// Returns how many times the polyline winds anticlockwise around the point, looking down the plane's Z axis.
+/// The polyline is taken as closed whether or not it ends where it starts
+pub fn winding_number(polyline:&PolyLine, plane:&Plane, point:&Point3d) -> i64 {
+    let points = loop_points(polyline, plane, &Tolerance::EXACT);
+    let point = plane.project(point);
+    (0..points.len())
+        .map(|k| crossing_sign(points[k], points[(k + 1) % points.len()], point))
+        .sum()
+}
+
+/// Returns whether the point lies inside, outside or on a closed polyline. A point is inside if the polyline winds around it
+/// at all, so parts wound twice over are still inside. An open polyline has no inside, but points on it are still on its boundary
+pub fn contains(polyline:&PolyLine, plane:&Plane, point:&Point3d, tolerance:&Tolerance) -> Containment {
+    let flat = plane.project(point);
+    let on_boundary = polyline.points().windows(2)
+        .any(|pair| Line2d::new(plane.project(&pair[0]), plane.project(&pair[1])).distance_to(&flat) <= tolerance.absolute);
+
+    if on_boundary {
+        Containment::Boundary
+    } else if polyline.is_closed(tolerance) && winding_number(polyline, plane, point) != 0 {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+/// Returns every place where one segment of the polyline crosses or touches another.
+/// Neighbouring segments always share a point, so they are only reported if one doubles back along the other.
+/// For a closed polyline the last and first segments are neighbours too. Repeated points should be removed first,
+/// as a segment with no length touches both its neighbours
+pub fn self_intersections(polyline:&PolyLine, plane:&Plane, tolerance:&Tolerance) -> Vec<SelfIntersection> {
+    let points:Vec<Point2d> = polyline.points().iter().map(|point| plane.project(point)).collect();
+    let segments:Vec<Line2d> = points.windows(2).map(|pair| Line2d::new(pair[0], pair[1])).collect();
+    let closed = polyline.is_closed(tolerance);
+    let count = segments.len();
+
+    let mut found = Vec::new();
+    for first in 0..count {
+        for second in first + 1..count {
+            let (a, b) = (&segments[first], &segments[second]);
+            let hit = if second == first + 1 {
+                doubles_back(a.start, b.end, a, b, tolerance)
+            } else if closed && first == 0 && second == count - 1 {
+                doubles_back(b.start, a.end, b, a, tolerance)
+            } else {
+                meeting_point(a, b, tolerance)
+            };
+
+            if let Some(point) = hit {
+                found.push(SelfIntersection { first, second, point:plane.lift(&point) });
+            }
+        }
+    }
+
+    found
+}
+
+/// Returns true if the polyline is closed and every corner turns the same way, going round only once.
+/// Corners turning by less than the angle tolerance count as straight
+pub fn is_convex(polyline:&PolyLine, plane:&Plane, tolerance:&Tolerance) -> bool {
+    if !polyline.is_closed(tolerance) {
+        return false;
+    }
+
+    let mut points = loop_points(polyline, plane, tolerance);
+    points.dedup_by(|a, b| a.distance_to(b) <= tolerance.absolute);
+    let count = points.len();
+    let (mut turns, mut total, mut direction) = (0, 0f64, 0f64);
+    for k in 0..count {
+        let arriving = points[k] - points[(k + count - 1) % count];
+        let leaving = points[(k + 1) % count] - points[k];
+        let turn = arriving.signed_angle_to(&leaving);
+        if turn.abs() <= tolerance.angle {
+            continue;
+        }
+        if PI - turn.abs() <= tolerance.angle || turn * direction < 0f64 {
+            return false;
+        }
+
+        direction = turn.signum();
+        total += turn;
+        turns += 1;
+    }
+
+    // Going round twice or more, as a star does, turns by a multiple of a full turn
+    turns >= 3 && (total.abs() - 2f64 * PI).abs() < PI
+}
+
+/// Cuts the region inside `outer` and outside each of `holes` into triangles by ear clipping.
+///
+/// Each triangle is three indices into the points of the outline followed by the points of each hole in turn,
+/// leaving out the last point of each as it closes the loop within tolerance. Triangles wind anticlockwise looking down the plane's Z axis,
+/// whichever way the outline and holes wind. Each hole is joined to the outline by a cut to the nearest point it can see,
+/// so holes must lie inside the outline without crossing it or each other. Points in the middle of straight edges are
+/// dropped where they would need a triangle with no area
+pub fn triangulate(outer:&PolyLine, holes:&[PolyLine], plane:&Plane, tolerance:&Tolerance) -> Result<Vec<[usize; 3]>, TriangulationError> {
+    if !outer.is_closed(tolerance) || holes.iter().any(|hole| !hole.is_closed(tolerance)) {
+        return Err(TriangulationError::NotClosed);
+    }
+
+    let mut points = loop_points(outer, plane, tolerance);
+    let mut ring:Vec<usize> = (0..points.len()).collect();
+    let area = ring_area(&points, &ring);
+    if area.abs() <= tolerance.absolute * tolerance.absolute {
+        return Err(TriangulationError::Degenerate);
+    }
+    if area < 0f64 {
+        ring.reverse();
+    }
+
+    let mut hole_rings = Vec::with_capacity(holes.len());
+    for hole in holes {
+        let start = points.len();
+        points.extend(loop_points(hole, plane, tolerance));
+        let mut hole_ring:Vec<usize> = (start..points.len()).collect();
+        if ring_area(&points, &hole_ring) > 0f64 {
+            hole_ring.reverse();
+        }
+        hole_rings.push(hole_ring);
+    }
+
+    // Bridging from the rightmost hole first, the holes still to come never block the way out to the outline
+    let rightmost = |hole:&Vec<usize>| hole.iter().map(|index| points[*index].x).fold(f64::NEG_INFINITY, f64::max);
+    hole_rings.sort_by(|a, b| rightmost(b).total_cmp(&rightmost(a)));
+    for index in 0..hole_rings.len() {
+        ring = bridge(&points, ring, &hole_rings[index], &hole_rings[index + 1..], tolerance)?;
+    }
+
+    clip_ears(&points, ring)
+}
+
+/// Returns the points of a polyline in the plane, leaving out the last point if the polyline is closed
+fn loop_points(polyline:&PolyLine, plane:&Plane, tolerance:&Tolerance) -> Vec<Point2d> {
+    let mut points:Vec<Point2d> = polyline.points().iter().map(|point| plane.project(point)).collect();
+    if polyline.is_closed(tolerance) {
+        points.pop();
+    }
+
+    points
+}
+
+/// Returns the area enclosed by a ring of indices into the points, positive if it winds anticlockwise
+fn ring_area(points:&[Point2d], ring:&[usize]) -> f64 {
+    signed_area(ring.iter().map(|index| points[*index]))
+}
+
+/// Returns where `b` runs back along its neighbour `a`, given the ends of each away from the point they share
+fn doubles_back(a_far:Point2d, b_far:Point2d, a:&Line2d, b:&Line2d, tolerance:&Tolerance) -> Option<Point2d> {
+    if a.distance_to(&b_far) <= tolerance.absolute {
+        Some(b_far)
+    } else if b.distance_to(&a_far) <= tolerance.absolute {
+        Some(a_far)
+    } else {
+        None
+    }
+}
+
+/// Returns where two segments cross, or an end of one lying within tolerance of the other
+fn meeting_point(a:&Line2d, b:&Line2d, tolerance:&Tolerance) -> Option<Point2d> {
+    let (a0, a1) = (b.side_of(&a.start), b.side_of(&a.end));
+    let (b0, b1) = (a.side_of(&b.start), a.side_of(&b.end));
+    if a0 * a1 < 0f64 && b0 * b1 < 0f64 {
+        return Some(a.point_at(a0 / (a0 - a1)));
+    }
+
+    [(a, b.start), (a, b.end), (b, a.start), (b, a.end)].into_iter()
+        .find(|(line, point)| line.distance_to(point) <= tolerance.absolute)
+        .map(|(_, point)| point)
+}
+
+/// Splices a clockwise hole into the anticlockwise ring with a cut from its rightmost point to the nearest point of the ring
+/// it can see, going out along the cut, round the hole and back
+fn bridge(points:&[Point2d], ring:Vec<usize>, hole:&[usize], later:&[Vec<usize>], tolerance:&Tolerance) -> Result<Vec<usize>, TriangulationError> {
+    let start = (0..hole.len())
+        .max_by(|a, b| points[hole[*a]].x.total_cmp(&points[hole[*b]].x))
+        .unwrap_or(0);
+    let from = points[hole[start]];
+
+    let mut candidates:Vec<usize> = (0..ring.len()).collect();
+    candidates.sort_by(|a, b| points[ring[*a]].distance_to(&from).total_cmp(&points[ring[*b]].distance_to(&from)));
+
+    let count = ring.len();
+    let visible = candidates.into_iter().find(|position| {
+        let (before, at, after) = (points[ring[(position + count - 1) % count]], points[ring[*position]], points[ring[(position + 1) % count]]);
+        if at == from {
+            return true;
+        }
+
+        let cut = Line2d::new(from, at);
+        let rings = std::iter::once(&ring[..]).chain(std::iter::once(hole)).chain(later.iter().map(|other| &other[..]));
+        in_cone(before, at, after, from) && rings.into_iter().all(|other| !blocks(points, other, &cut, tolerance))
+    });
+    let Some(position) = visible else {
+        return Err(TriangulationError::Degenerate);
+    };
+
+    let mut spliced = Vec::with_capacity(ring.len() + hole.len() + 2);
+    spliced.extend_from_slice(&ring[..=position]);
+    spliced.extend(hole[start..].iter().chain(&hole[..=start]));
+    spliced.extend_from_slice(&ring[position..]);
+    Ok(spliced)
+}
+
+/// Returns true if the point is strictly within the angle the anticlockwise ring makes inside itself at `at`
+fn in_cone(before:Point2d, at:Point2d, after:Point2d, point:Point2d) -> bool {
+    let left_of = |start:Point2d, end:Point2d| (end - start).cross(&(point - start)) > 0f64;
+    if (at - before).cross(&(after - at)) >= 0f64 {
+        left_of(before, at) && left_of(at, after)
+    } else {
+        left_of(before, at) || left_of(at, after)
+    }
+}
+
+/// Returns true if the cut crosses an edge of the ring, or passes over one of its points other than the cut's own ends
+fn blocks(points:&[Point2d], ring:&[usize], cut:&Line2d, tolerance:&Tolerance) -> bool {
+    let count = ring.len();
+    (0..count).any(|k| {
+        let (a, b) = (points[ring[k]], points[ring[(k + 1) % count]]);
+        let (a0, a1) = (cut.side_of(&a), cut.side_of(&b));
+        let edge = Line2d::new(a, b);
+        let (c0, c1) = (edge.side_of(&cut.start), edge.side_of(&cut.end));
+        let crosses = a0 * a1 < 0f64 && c0 * c1 < 0f64;
+        let passes_over = a != cut.start && a != cut.end && cut.distance_to(&a) <= tolerance.absolute;
+        crosses || passes_over
+    })
+}
+
+/// Cuts triangles off an anticlockwise ring one corner at a time, taking each convex corner with no other point in the way
+fn clip_ears(points:&[Point2d], mut ring:Vec<usize>) -> Result<Vec<[usize; 3]>, TriangulationError> {
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    let mut position = 0;
+    while ring.len() > 3 {
+        let count = ring.len();
+        let corner = |k:usize| [ring[(k + count - 1) % count], ring[k], ring[(k + 1) % count]];
+        let turn = |k:usize| {
+            let [a, b, c] = corner(k);
+            (points[b] - points[a]).cross(&(points[c] - points[b]))
+        };
+
+        let ear = (0..count).map(|k| (position + k) % count).find(|k| {
+            let [a, b, c] = corner(*k);
+            turn(*k) > 0f64 && !ring.iter().any(|other| in_triangle(points, [a, b, c], points[*other]))
+        });
+
+        if let Some(k) = ear {
+            triangles.push(corner(k));
+            ring.remove(k);
+            position = k;
+            continue;
+        }
+
+        // A corner with no turn needs no triangle
+        let Some(k) = (0..count).find(|k| turn(*k) == 0f64) else {
+            return Err(TriangulationError::Degenerate);
+        };
+        ring.remove(k);
+        position = k;
+    }
+
+    if ring.len() == 3 && ring_area(points, &ring) > 0f64 {
+        triangles.push([ring[0], ring[1], ring[2]]);
+    }
+
+    Ok(triangles)
+}
+
+/// Returns true if the point lies inside or on an anticlockwise triangle, but is not one of its corners
+fn in_triangle(points:&[Point2d], triangle:[usize; 3], point:Point2d) -> bool {
+    let [a, b, c] = triangle.map(|index| points[index]);
+    if point == a || point == b || point == c {
+        return false;
+    }
+
+    (b - a).cross(&(point - a)) >= 0f64 &&
+    (c - b).cross(&(point - b)) >= 0f64 &&
+    (a - c).cross(&(point - c)) >= 0f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EpsilonEquals::EpsilonEquals;
+    use crate::geometry::basics::Vector3d::Vector3d;
+
+    const TOLERANCE:Tolerance = Tolerance::DEFAULT;
+
+    fn polyline(points:&[(f64, f64)]) -> PolyLine {
+        PolyLine::new(points.iter().map(|(x, y)| Point3d::new(*x, *y, 0f64)).collect())
+    }
+
+    fn square(min:f64, max:f64) -> PolyLine {
+        polyline(&[(min, min), (max, min), (max, max), (min, max), (min, min)])
+    }
+
+    /// Returns the total area of the triangles, checking each winds anticlockwise
+    fn triangles_area(loops:&[&PolyLine], triangles:&[[usize; 3]]) -> f64 {
+        let points:Vec<Point2d> = loops.iter()
+            .flat_map(|polyline| loop_points(polyline, &Plane::WORLDXY, &TOLERANCE))
+            .collect();
+        triangles.iter()
+            .map(|[a, b, c]| {
+                let area = (points[*b] - points[*a]).cross(&(points[*c] - points[*a])) * 0.5f64;
+                assert!(area > 0f64);
+                area
+            })
+            .sum()
+    }
+
+    #[test]
+    pub fn point_containment() {
+        let plane = Plane::WORLDXY;
+        let square = square(0f64, 2f64);
+        let at = |x:f64, y:f64| Point3d::new(x, y, 0f64);
+
+        assert_eq!(Containment::Inside, contains(&square, &plane, &at(1f64, 1f64), &TOLERANCE));
+        assert_eq!(Containment::Outside, contains(&square, &plane, &at(3f64, 1f64), &TOLERANCE));
+        assert_eq!(Containment::Boundary, contains(&square, &plane, &at(2f64, 1f64), &TOLERANCE));
+        assert_eq!(Containment::Boundary, contains(&square, &plane, &at(2.0005f64, 1f64), &TOLERANCE));
+        assert_eq!(Containment::Outside, contains(&square, &plane, &at(2.0005f64, 1f64), &Tolerance::EXACT));
+
+        // Going round twice still contains the middle, and going round the other way counts backwards
+        let twice = polyline(&[(0f64, 0f64), (2f64, 0f64), (2f64, 2f64), (0f64, 2f64), (0f64, 0f64),
+                               (2f64, 0f64), (2f64, 2f64), (0f64, 2f64), (0f64, 0f64)]);
+        assert_eq!(2, winding_number(&twice, &plane, &at(1f64, 1f64)));
+        let mut clockwise = square.clone();
+        clockwise.reverse();
+        assert_eq!(-1, winding_number(&clockwise, &plane, &at(1f64, 1f64)));
+        assert_eq!(Containment::Inside, contains(&clockwise, &plane, &at(1f64, 1f64), &TOLERANCE));
+    }
+
+    #[test]
+    pub fn finds_self_intersections() {
+        let plane = Plane::WORLDXY;
+        let bowtie = polyline(&[(0f64, 0f64), (2f64, 2f64), (2f64, 0f64), (0f64, 2f64), (0f64, 0f64)]);
+        let found = self_intersections(&bowtie, &plane, &TOLERANCE);
+
+        assert_eq!(1, found.len());
+        assert_eq!((0, 2), (found[0].first, found[0].second));
+        assert!(found[0].point.epsilon_equals(&Point3d::new(1f64, 1f64, 0f64), &TOLERANCE));
+        assert!(self_intersections(&square(0f64, 2f64), &plane, &TOLERANCE).is_empty());
+
+        // Touching a vertex and doubling back both count
+        let touching = polyline(&[(0f64, 0f64), (4f64, 0f64), (4f64, 4f64), (2f64, 0f64)]);
+        assert_eq!(1, self_intersections(&touching, &plane, &TOLERANCE).len());
+        let spike = polyline(&[(0f64, 0f64), (4f64, 0f64), (2f64, 0f64)]);
+        assert_eq!(1, self_intersections(&spike, &plane, &TOLERANCE).len());
+    }
+
+    #[test]
+    pub fn convexity() {
+        let plane = Plane::WORLDXY;
+        assert!(is_convex(&square(0f64, 2f64), &plane, &TOLERANCE));
+
+        let mut clockwise = polyline(&[(0f64, 0f64), (1f64, 0f64), (2f64, 0f64), (2f64, 2f64), (0f64, 2f64), (0f64, 0f64)]);
+        clockwise.reverse();
+        assert!(is_convex(&clockwise, &plane, &TOLERANCE));
+
+        let ell = polyline(&[(0f64, 0f64), (2f64, 0f64), (2f64, 1f64), (1f64, 1f64), (1f64, 2f64), (0f64, 2f64), (0f64, 0f64)]);
+        assert!(!is_convex(&ell, &plane, &TOLERANCE));
+
+        let mut star = Vec::new();
+        for k in 0..=5 {
+            let angle = k as f64 * 4f64 * PI / 5f64;
+            star.push((angle.cos(), angle.sin()));
+        }
+        assert!(!is_convex(&polyline(&star), &plane, &TOLERANCE));
+        assert!(!is_convex(&polyline(&[(0f64, 0f64), (2f64, 0f64), (2f64, 2f64)]), &plane, &TOLERANCE));
+    }
+
+    #[test]
+    pub fn triangulates_simple_outlines() {
+        let plane = Plane::WORLDXY;
+        let square = square(0f64, 2f64);
+        let triangles = triangulate(&square, &[], &plane, &TOLERANCE).unwrap();
+        assert_eq!(2, triangles.len());
+        assert!(TOLERANCE.equals(4f64, triangles_area(&[&square], &triangles)));
+
+        let mut ell = polyline(&[(0f64, 0f64), (2f64, 0f64), (2f64, 1f64), (1f64, 1f64), (1f64, 2f64), (0f64, 2f64), (0f64, 0f64)]);
+        ell.reverse();
+        let triangles = triangulate(&ell, &[], &plane, &TOLERANCE).unwrap();
+        assert_eq!(4, triangles.len());
+        assert!(TOLERANCE.equals(3f64, triangles_area(&[&ell], &triangles)));
+
+        assert_eq!(Err(TriangulationError::NotClosed), triangulate(&polyline(&[(0f64, 0f64), (1f64, 0f64), (1f64, 1f64)]), &[], &plane, &TOLERANCE));
+        let flat = polyline(&[(0f64, 0f64), (1f64, 0f64), (2f64, 0f64), (0f64, 0f64)]);
+        assert_eq!(Err(TriangulationError::Degenerate), triangulate(&flat, &[], &plane, &TOLERANCE));
+    }
+
+    #[test]
+    pub fn triangulates_around_holes() {
+        let plane = Plane::WORLDXY;
+        let outer = polyline(&[(0f64, 0f64), (10f64, 0f64), (10f64, 6f64), (0f64, 6f64), (0f64, 0f64)]);
+        let left = square(1f64, 3f64);
+        let right = polyline(&[(6f64, 2f64), (8f64, 2f64), (7f64, 4f64), (6f64, 2f64)]);
+
+        let triangles = triangulate(&outer, &[left.clone(), right.clone()], &plane, &TOLERANCE).unwrap();
+        // Every point is used, and joining each hole adds two triangles
+        assert_eq!(4 + 4 + 3 + 2 * 2 - 2, triangles.len());
+        assert!((0..11).all(|index| triangles.iter().flatten().any(|corner| *corner == index)));
+        assert!(TOLERANCE.equals(60f64 - 4f64 - 2f64, triangles_area(&[&outer, &left, &right], &triangles)));
+    }
+
+    #[test]
+    pub fn indices_skip_nearly_repeated_ends() {
+        let outer = square(0f64, 4f64);
+        let hole = polyline(&[(1f64, 1f64), (1f64, 3f64), (3f64, 3f64), (3f64, 1f64), (1.0002f64, 1f64)]);
+
+        let triangles = triangulate(&outer, std::slice::from_ref(&hole), &Plane::WORLDXY, &TOLERANCE).unwrap();
+        assert!(triangles.iter().flatten().all(|index| *index < 8));
+        assert!(TOLERANCE.equals(12f64, triangles_area(&[&outer, &hole], &triangles)));
+    }
+
+    #[test]
+    pub fn triangulates_in_a_tilted_plane() {
+        let plane = Plane::from_point_normal(Point3d::new(0f64, 0f64, 5f64), Vector3d::new(0f64, 1f64, 1f64)).unwrap();
+        let outline = PolyLine::new(vec![plane.point_at(0f64, 0f64, 0f64), plane.point_at(4f64, 0f64, 0f64),
+                                         plane.point_at(4f64, 4f64, 0f64), plane.point_at(0f64, 4f64, 0f64),
+                                         plane.point_at(0f64, 0f64, 0f64)]);
+
+        assert_eq!(2, triangulate(&outline, &[], &plane, &TOLERANCE).unwrap().len());
+        assert!(is_convex(&outline, &plane, &TOLERANCE));
+        assert_eq!(Containment::Inside, contains(&outline, &plane, &plane.point_at(1f64, 1f64, 0f64), &TOLERANCE));
+    }
+
+}
@@ -1,2 +1,8 @@
+// Modules and files are named after the types they hold, following the Rhino naming
+#![allow(non_snake_case)]
+
 pub mod geometry;
+pub mod EpsilonEquals;
 pub mod IsValid;
+pub mod Tolerance;
+pub mod Transformable;